@@ -0,0 +1,105 @@
+//! Common return type for every part function
+//!
+//! Days pick whichever integer type suits the puzzle; converting the result
+//! into an [`Answer`] lets the runner print and compare results without
+//! knowing each day's type.
+
+use std::fmt;
+
+/// The answer to one part of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    /// A signed integer answer
+    Signed(i64),
+    /// An unsigned integer answer
+    Unsigned(u64),
+    /// An integer too large for 64 bits
+    Big(i128),
+    /// A non-numeric answer (e.g. a code or a rendered word)
+    Text(String),
+    /// The part has no puzzle to solve (e.g. day 12 part 2)
+    NotApplicable,
+}
+
+impl Answer {
+    /// Whether this part produced an answer at all
+    pub fn is_applicable(&self) -> bool {
+        !matches!(self, Answer::NotApplicable)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Signed(n) => write!(f, "{}", n),
+            Answer::Unsigned(n) => write!(f, "{}", n),
+            Answer::Big(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::NotApplicable => write!(f, "n/a"),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Signed(n)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Self {
+        Answer::Unsigned(n)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Unsigned(n as u64)
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(n: i128) -> Self {
+        Answer::Big(n)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_integers() {
+        assert_eq!(Answer::from(-3i64), Answer::Signed(-3));
+        assert_eq!(Answer::from(7u64), Answer::Unsigned(7));
+        assert_eq!(Answer::from(7usize), Answer::Unsigned(7));
+        assert_eq!(Answer::from(1i128 << 80), Answer::Big(1 << 80));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::Signed(-42).to_string(), "-42");
+        assert_eq!(Answer::Unsigned(42).to_string(), "42");
+        assert_eq!(Answer::Big(1 << 70).to_string(), "1180591620717411303424");
+        assert_eq!(Answer::from("ABC").to_string(), "ABC");
+        assert_eq!(Answer::NotApplicable.to_string(), "n/a");
+    }
+
+    #[test]
+    fn test_is_applicable() {
+        assert!(Answer::Signed(0).is_applicable());
+        assert!(!Answer::NotApplicable.is_applicable());
+    }
+}
@@ -0,0 +1,326 @@
+//! Repeated timing of parsing and solving, with a saved baseline
+//!
+//! Each phase is run a few times to warm caches, then sampled. The median of
+//! every phase can be saved to a JSON baseline (by default `bench.json` in
+//! the puzzles root, since timings depend on the input), and later runs show
+//! how far each phase has moved from it.
+
+use crate::runner::{self, RunError};
+use crate::solution::Puzzle;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// File name of the baseline inside the puzzles root
+pub const BASELINE_FILE: &str = "bench.json";
+
+/// How many times to run each phase
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// Untimed runs before sampling starts
+    pub warmup: usize,
+    /// Timed runs; must be at least 1
+    pub samples: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            warmup: 3,
+            samples: 10,
+        }
+    }
+}
+
+/// Summary statistics over a set of timing samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    /// Sample standard deviation (zero for a single sample)
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Summarise `samples`, which must not be empty
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples to summarise");
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let n = sorted.len();
+        let median = if n % 2 == 1 {
+            sorted[n / 2]
+        } else {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        };
+
+        let secs: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// The step of a day being measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Part(u8),
+}
+
+impl Phase {
+    /// Key identifying this phase of `day` in the baseline file
+    pub fn key(&self, day: u8) -> String {
+        match self {
+            Phase::Parse => format!("day{:02}.parse", day),
+            Phase::Part(part) => format!("day{:02}.part{}", day, part),
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Part(part) => write!(f, "part {}", part),
+        }
+    }
+}
+
+/// Timings for every measured phase of one day
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    pub day: u8,
+    pub title: &'static str,
+    pub phases: Vec<(Phase, Stats)>,
+    /// Parts that failed or panicked, which have no timings
+    pub failures: Vec<(Phase, RunError)>,
+}
+
+/// Benchmark parsing and either one part or both
+///
+/// Fails if the input can't be parsed, since there is nothing left to time.
+/// A part that fails is recorded in [`BenchReport::failures`] and the other
+/// part is still timed.
+pub fn bench_day(
+    puzzle: &dyn Puzzle,
+    raw: &str,
+    lenient: bool,
+    part: Option<u8>,
+    config: &BenchConfig,
+) -> Result<BenchReport, RunError> {
+    let parse = || runner::parse(puzzle, raw, lenient);
+
+    for _ in 0..config.warmup {
+        parse()?;
+    }
+    let mut samples = Vec::with_capacity(config.samples);
+    let mut input = None;
+    for _ in 0..config.samples {
+        let start = Instant::now();
+        let parsed = parse()?;
+        samples.push(start.elapsed());
+        input = Some(parsed);
+    }
+    let input = input.expect("at least one sample");
+    let mut phases = vec![(Phase::Parse, Stats::from_samples(&samples))];
+    let mut failures = Vec::new();
+
+    for solve_part in [1, 2]
+        .into_iter()
+        .filter(|&p| part.is_none_or(|want| want == p))
+    {
+        let solve = || runner::solve(puzzle, &input, solve_part);
+        let timed = (0..config.warmup)
+            .try_for_each(|_| solve().map(drop))
+            .and_then(|()| {
+                (0..config.samples)
+                    .map(|_| {
+                        let start = Instant::now();
+                        solve()?;
+                        Ok(start.elapsed())
+                    })
+                    .collect::<Result<Vec<Duration>, RunError>>()
+            });
+        match timed {
+            Ok(samples) => phases.push((Phase::Part(solve_part), Stats::from_samples(&samples))),
+            Err(err) => failures.push((Phase::Part(solve_part), err)),
+        }
+    }
+
+    Ok(BenchReport {
+        day: puzzle.day(),
+        title: puzzle.title(),
+        phases,
+        failures,
+    })
+}
+
+/// Median nanoseconds per phase, keyed by [`Phase::key`]
+pub type Baseline = BTreeMap<String, u64>;
+
+/// Store the medians from `report` in `baseline`, replacing older entries
+pub fn record(baseline: &mut Baseline, report: &BenchReport) {
+    for (phase, stats) in &report.phases {
+        baseline.insert(phase.key(report.day), stats.median.as_nanos() as u64);
+    }
+}
+
+/// Percentage change of `now` relative to a baseline median
+pub fn change(now: Duration, before_nanos: u64) -> f64 {
+    if before_nanos == 0 {
+        return 0.0;
+    }
+    (now.as_nanos() as f64 - before_nanos as f64) / before_nanos as f64 * 100.0
+}
+
+/// Serialise a baseline as a flat JSON object
+pub fn render_baseline(baseline: &Baseline) -> String {
+    let entries: Vec<String> = baseline
+        .iter()
+        .map(|(key, nanos)| format!("  \"{}\": {}", key, nanos))
+        .collect();
+    format!("{{\n{}\n}}\n", entries.join(",\n"))
+}
+
+/// Read a baseline written by [`render_baseline`]
+///
+/// Accepts any flat JSON object whose keys are plain strings (no escapes) and
+/// whose values are non-negative integers.
+pub fn parse_baseline(text: &str) -> Result<Baseline, String> {
+    let body = text
+        .trim()
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+        .ok_or("baseline must be a JSON object")?
+        .trim();
+
+    let mut baseline = Baseline::new();
+    if body.is_empty() {
+        return Ok(baseline);
+    }
+    for entry in body.split(',') {
+        let (key, value) = entry
+            .split_once(':')
+            .ok_or_else(|| format!("expected \"key\": value, found '{}'", entry.trim()))?;
+        let key = key
+            .trim()
+            .strip_prefix('"')
+            .and_then(|key| key.strip_suffix('"'))
+            .filter(|key| !key.contains(['"', '\\']))
+            .ok_or_else(|| format!("invalid key {}", key.trim()))?;
+        let nanos = value
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid timing for '{}': {}", key, value.trim()))?;
+        baseline.insert(key.to_string(), nanos);
+    }
+    Ok(baseline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&v| Duration::from_millis(v)).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(&ms(&[4, 2, 8, 6]));
+        assert_eq!(stats.min, Duration::from_millis(2));
+        assert_eq!(stats.median, Duration::from_millis(5));
+        assert_eq!(stats.mean, Duration::from_millis(5));
+        // Sample variance: (9 + 1 + 1 + 9) / 3
+        assert!((stats.stddev.as_secs_f64() - 0.002582).abs() < 1e-6);
+
+        let single = Stats::from_samples(&ms(&[3]));
+        assert_eq!(single.median, Duration::from_millis(3));
+        assert_eq!(single.stddev, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_bench_day() {
+        let config = BenchConfig {
+            warmup: 1,
+            samples: 3,
+        };
+        let puzzle = days::get(1).unwrap();
+        let report = bench_day(puzzle, "L68\nL30\nR48", false, None, &config).unwrap();
+        let phases: Vec<Phase> = report.phases.iter().map(|(phase, _)| *phase).collect();
+        assert_eq!(phases, [Phase::Parse, Phase::Part(1), Phase::Part(2)]);
+
+        let report = bench_day(puzzle, "L68", false, Some(2), &config).unwrap();
+        assert_eq!(report.phases[1].0, Phase::Part(2));
+        assert_eq!(report.phases.len(), 2);
+
+        assert!(matches!(
+            bench_day(puzzle, "bogus", false, None, &config),
+            Err(RunError::Parse(_))
+        ));
+
+        // An unsolvable machine fails its part, and the other part is
+        // still timed
+        let report = bench_day(days::get(10).unwrap(), "[..] (0) {1,1}", false, None, &config).unwrap();
+        let phases: Vec<Phase> = report.phases.iter().map(|(phase, _)| *phase).collect();
+        assert_eq!(phases, [Phase::Parse, Phase::Part(1)]);
+        assert_eq!(
+            report.failures,
+            [(
+                Phase::Part(2),
+                RunError::Failed("machine 1: no solution satisfies the constraints".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let mut baseline = Baseline::new();
+        let report = BenchReport {
+            day: 3,
+            title: "Test Day",
+            phases: vec![
+                (Phase::Parse, Stats::from_samples(&ms(&[1]))),
+                (Phase::Part(2), Stats::from_samples(&ms(&[2]))),
+            ],
+            failures: Vec::new(),
+        };
+        record(&mut baseline, &report);
+
+        let json = render_baseline(&baseline);
+        assert_eq!(
+            json,
+            "{\n  \"day03.parse\": 1000000,\n  \"day03.part2\": 2000000\n}\n"
+        );
+        assert_eq!(parse_baseline(&json), Ok(baseline));
+        assert_eq!(parse_baseline("{}"), Ok(Baseline::new()));
+    }
+
+    #[test]
+    fn test_parse_baseline_errors() {
+        assert!(parse_baseline("[]").is_err());
+        assert!(parse_baseline("{\"day01.parse\": -5}").is_err());
+        assert!(parse_baseline("{day01: 5}").is_err());
+    }
+
+    #[test]
+    fn test_change() {
+        assert_eq!(change(Duration::from_nanos(150), 100), 50.0);
+        assert_eq!(change(Duration::from_nanos(75), 100), -25.0);
+        assert_eq!(change(Duration::from_nanos(75), 0), 0.0);
+    }
+}
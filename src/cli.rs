@@ -0,0 +1,337 @@
+//! Command-line argument parsing for the runner binary
+
+use crate::bench::BenchConfig;
+use crate::input::InputSource;
+use crate::output::Format;
+use crate::pool::PoolConfig;
+use crate::report::DEFAULT_BUDGET;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// What the runner should do
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Print usage and the list of available days
+    Help,
+    /// Run a single day
+    Run { day: u8 },
+    /// Run every registered day and print a timing table
+    All,
+    /// Check one or every day against its recorded answers
+    Verify { day: Option<u8> },
+    /// Time parsing and solving for one or every day, optionally one part
+    Bench { day: Option<u8>, part: Option<u8> },
+}
+
+/// Parsed command line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cli {
+    pub command: Command,
+    /// Skip malformed input lines instead of failing
+    pub lenient: bool,
+    /// How results of a day or `all` are printed
+    pub format: Format,
+    /// Per-part time budget; slower parts are flagged in the table
+    pub budget: Duration,
+    /// Worker threads and per-task time limit for `all`
+    pub pool: PoolConfig,
+    /// Where each day's input is read from
+    pub input: InputSource,
+    /// Puzzles root given on the command line, if any
+    pub puzzles_dir: Option<PathBuf>,
+    /// Warm-up and sample counts for `bench`
+    pub bench: BenchConfig,
+    /// Baseline file for `bench`; defaults to `bench.json` in the puzzles root
+    pub baseline: Option<PathBuf>,
+    /// Write this `bench` run's medians to the baseline
+    pub save_baseline: bool,
+}
+
+pub const USAGE: &str = "Usage: cargo run -- <day|all|verify [day]|bench [day] [part]> [options]
+
+Options:
+  --lenient          Skip malformed input lines instead of failing
+  --format <fmt>     Print results as text, json or csv (default: text)
+  --budget <secs>    Flag parts slower than this in `all` (default: 15)
+  --jobs <n>         Run `all` on n worker threads (default: 1)
+  --timeout <secs>   Give up on any parse or part in `all` after this long
+  --input <path>     Read input from <path> instead of the puzzles directory
+  -                  Read input from stdin (same as `--input -`)
+  --example <n>      Use the nth example stored alongside the puzzle
+  --puzzles <dir>    Puzzles root (default: $AOC_PUZZLES_DIR or `puzzles`)
+  --warmup <n>       Untimed runs before `bench` samples (default: 3)
+  --samples <n>      Timed runs per phase in `bench` (default: 10)
+  --baseline <path>  Baseline for `bench` (default: bench.json in the puzzles root)
+  --save             Save this `bench` run as the new baseline";
+
+/// Parse the arguments following the program name
+pub fn parse_args<I>(args: I) -> Result<Cli, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut cli = Cli {
+        command: Command::Help,
+        lenient: false,
+        format: Format::Text,
+        budget: DEFAULT_BUDGET,
+        pool: PoolConfig::default(),
+        input: InputSource::Puzzle,
+        puzzles_dir: None,
+        bench: BenchConfig::default(),
+        baseline: None,
+        save_baseline: false,
+    };
+    let mut input_flag: Option<&str> = None;
+    let mut positional = Vec::new();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lenient" => cli.lenient = true,
+            "--format" => {
+                cli.format = args.next().ok_or("--format requires text, json or csv")?.parse()?;
+            }
+            "--budget" => cli.budget = parse_seconds("--budget", args.next())?,
+            "--jobs" => cli.pool.jobs = parse_count("--jobs", args.next(), 1)?,
+            "--timeout" => cli.pool.timeout = Some(parse_seconds("--timeout", args.next())?),
+            "--input" | "-" => {
+                let source = match arg.as_str() {
+                    "-" => InputSource::Stdin,
+                    _ => match args.next().ok_or("--input requires a path")? {
+                        path if path == "-" => InputSource::Stdin,
+                        path => InputSource::File(PathBuf::from(path)),
+                    },
+                };
+                set_input(&mut cli, &mut input_flag, "--input", source)?;
+            }
+            "--example" => {
+                let value = args.next().ok_or("--example requires a number")?;
+                let n: usize = value
+                    .parse()
+                    .ok()
+                    .filter(|&n| n >= 1)
+                    .ok_or_else(|| format!("invalid example number '{}'", value))?;
+                set_input(&mut cli, &mut input_flag, "--example", InputSource::Example(n))?;
+            }
+            "--puzzles" => {
+                let dir = args.next().ok_or("--puzzles requires a directory")?;
+                cli.puzzles_dir = Some(PathBuf::from(dir));
+            }
+            "--warmup" => cli.bench.warmup = parse_count("--warmup", args.next(), 0)?,
+            "--samples" => cli.bench.samples = parse_count("--samples", args.next(), 1)?,
+            "--baseline" => {
+                let path = args.next().ok_or("--baseline requires a path")?;
+                cli.baseline = Some(PathBuf::from(path));
+            }
+            "--save" => cli.save_baseline = true,
+            "-h" | "--help" => return Ok(cli),
+            _ if arg.starts_with("--") => return Err(format!("unknown option '{}'", arg)),
+            _ => positional.push(arg),
+        }
+    }
+
+    cli.command = match positional.as_slice() {
+        [] => Command::Help,
+        [cmd] if cmd == "all" => Command::All,
+        [cmd] if cmd == "verify" => Command::Verify { day: None },
+        [cmd, day] if cmd == "verify" => Command::Verify {
+            day: Some(parse_day(day)?),
+        },
+        [cmd, rest @ ..] if cmd == "bench" && rest.len() <= 2 => Command::Bench {
+            day: rest.first().map(|day| parse_day(day)).transpose()?,
+            part: rest
+                .get(1)
+                .map(|part| match part.as_str() {
+                    "1" => Ok(1),
+                    "2" => Ok(2),
+                    _ => Err(format!("part must be 1 or 2, found '{}'", part)),
+                })
+                .transpose()?,
+        },
+        [day] => Command::Run {
+            day: day
+                .parse()
+                .map_err(|_| format!("day must be a number or 'all', found '{}'", day))?,
+        },
+        [_, extra, ..] => return Err(format!("unexpected argument '{}'", extra)),
+    };
+
+    match cli.command {
+        Command::All | Command::Bench { day: None, .. } if cli.input.is_shared() => {
+            return Err("`all` reads each day's own input; --input and stdin need a single day".into());
+        }
+        Command::Verify { .. } if cli.input != InputSource::Puzzle => {
+            return Err("`verify` checks the recorded answers for each day's puzzle input".into());
+        }
+        Command::Verify { .. } | Command::Bench { .. } if cli.format != Format::Text => {
+            return Err("--format applies to running a day or `all`".into());
+        }
+        _ => {}
+    }
+
+    Ok(cli)
+}
+
+/// Parse the value of a duration option given in (possibly fractional) seconds
+fn parse_seconds(flag: &str, value: Option<String>) -> Result<Duration, String> {
+    let value = value.ok_or_else(|| format!("{} requires a value in seconds", flag))?;
+    value
+        .parse()
+        .ok()
+        .filter(|secs: &f64| secs.is_finite() && *secs >= 0.0)
+        .map(Duration::from_secs_f64)
+        .ok_or_else(|| format!("invalid {} '{}'", flag, value))
+}
+
+/// Parse the value of a count option, which must be at least `min`
+fn parse_count(flag: &str, value: Option<String>, min: usize) -> Result<usize, String> {
+    let value = value.ok_or_else(|| format!("{} requires a number", flag))?;
+    value
+        .parse()
+        .ok()
+        .filter(|&n| n >= min)
+        .ok_or_else(|| format!("invalid {} count '{}'", flag, value))
+}
+
+fn parse_day(day: &str) -> Result<u8, String> {
+    day.parse()
+        .map_err(|_| format!("day must be a number, found '{}'", day))
+}
+
+/// Record the input source, rejecting a second, conflicting choice
+fn set_input(
+    cli: &mut Cli,
+    previous: &mut Option<&'static str>,
+    flag: &'static str,
+    source: InputSource,
+) -> Result<(), String> {
+    if let Some(previous) = previous.replace(flag) {
+        return Err(format!("{} cannot be combined with {}", flag, previous));
+    }
+    cli.input = source;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, String> {
+        parse_args(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_day() {
+        let cli = parse(&["7"]).unwrap();
+        assert_eq!(cli.command, Command::Run { day: 7 });
+        assert!(!cli.lenient);
+        assert_eq!(cli.format, Format::Text);
+        assert_eq!(cli.budget, DEFAULT_BUDGET);
+    }
+
+    #[test]
+    fn test_parse_all_with_options() {
+        let cli = parse(&["all", "--budget", "0.5", "--lenient"]).unwrap();
+        assert_eq!(cli.command, Command::All);
+        assert!(cli.lenient);
+        assert_eq!(cli.budget, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(parse(&["7", "--format", "json"]).unwrap().format, Format::Json);
+        assert_eq!(parse(&["all", "--format", "csv"]).unwrap().format, Format::Csv);
+        assert!(parse(&["7", "--format", "xml"]).is_err());
+        assert!(parse(&["7", "--format"]).is_err());
+        assert!(parse(&["verify", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_pool() {
+        assert_eq!(parse(&["all"]).unwrap().pool, PoolConfig::default());
+        let cli = parse(&["all", "--jobs", "4", "--timeout", "2.5"]).unwrap();
+        assert_eq!(
+            cli.pool,
+            PoolConfig {
+                jobs: 4,
+                timeout: Some(Duration::from_millis(2500)),
+            }
+        );
+        assert!(parse(&["all", "--jobs", "0"]).is_err());
+        assert!(parse(&["all", "--timeout", "soon"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_help() {
+        assert_eq!(parse(&[]).unwrap().command, Command::Help);
+        assert_eq!(parse(&["--help", "3"]).unwrap().command, Command::Help);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(&["seven"]).is_err());
+        assert!(parse(&["1", "2"]).is_err());
+        assert!(parse(&["all", "--budget"]).is_err());
+        assert!(parse(&["all", "--budget", "-1"]).is_err());
+        assert!(parse(&["all", "--fast"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_input_sources() {
+        assert_eq!(parse(&["3"]).unwrap().input, InputSource::Puzzle);
+        assert_eq!(
+            parse(&["3", "--input", "mine.txt"]).unwrap().input,
+            InputSource::File(PathBuf::from("mine.txt"))
+        );
+        assert_eq!(parse(&["3", "-"]).unwrap().input, InputSource::Stdin);
+        assert_eq!(parse(&["3", "--input", "-"]).unwrap().input, InputSource::Stdin);
+
+        let cli = parse(&["all", "--example", "2", "--puzzles", "/tmp/aoc"]).unwrap();
+        assert_eq!(cli.input, InputSource::Example(2));
+        assert_eq!(cli.puzzles_dir, Some(PathBuf::from("/tmp/aoc")));
+    }
+
+    #[test]
+    fn test_parse_input_errors() {
+        assert!(parse(&["3", "--example", "0"]).is_err());
+        assert!(parse(&["3", "--example", "1", "-"]).is_err());
+        assert!(parse(&["3", "--input"]).is_err());
+        assert!(parse(&["all", "--input", "mine.txt"]).is_err());
+        assert!(parse(&["all", "-"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_verify() {
+        assert_eq!(parse(&["verify"]).unwrap().command, Command::Verify { day: None });
+        assert_eq!(
+            parse(&["verify", "9", "--puzzles", "ci"]).unwrap().command,
+            Command::Verify { day: Some(9) }
+        );
+        assert!(parse(&["verify", "nine"]).is_err());
+        assert!(parse(&["verify", "9", "--example", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        let cli = parse(&["bench"]).unwrap();
+        assert_eq!(cli.command, Command::Bench { day: None, part: None });
+        assert_eq!(cli.bench, BenchConfig::default());
+        assert!(!cli.save_baseline);
+
+        let cli = parse(&["bench", "9", "2", "--warmup", "0", "--samples", "50", "--save"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Command::Bench {
+                day: Some(9),
+                part: Some(2)
+            }
+        );
+        assert_eq!(cli.bench, BenchConfig { warmup: 0, samples: 50 });
+        assert!(cli.save_baseline);
+
+        assert!(parse(&["bench", "9", "3"]).is_err());
+        assert!(parse(&["bench", "9", "1", "x"]).is_err());
+        assert!(parse(&["bench", "--samples", "0"]).is_err());
+        assert!(parse(&["bench", "-"]).is_err());
+    }
+}
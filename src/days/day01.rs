@@ -1,116 +1,618 @@
-/// Day 1: Secret Entrance
-///
-/// A safe dial goes from 0-99 in a circle. Starting at 50, follow rotation
-/// instructions and count how many times the dial points at 0.
+//! Day 1: Secret Entrance
+//!
+//! A safe dial goes from 0-99 in a circle. Starting at 50, follow rotation
+//! instructions and count how many times the dial points at 0.
+//!
+//! Both parts are queries over the events of a `Dial`, which also takes other
+//! sizes, start positions and several targets at once.
+//!
+//! Input is a small instruction language: `L<n>` and `R<n>` turn the dial,
+//! `=<n>` points it straight at a position, `x<k>{ ... }` repeats a block `k`
+//! times (blocks nest) and `#` starts a comment.
+
+use crate::answer::Answer;
+use crate::error::{column_of, parse_number, ParseError};
+use crate::solution::Solution;
+use crate::utils::num::floor_div;
+use std::collections::VecDeque;
+
+/// One step of a dial program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    /// Turn left (direction -1) or right (1) by `distance` clicks
+    Turn { direction: i64, distance: i64 },
+    /// Point the dial straight at a position, without clicking past anything
+    Set(i64),
+    /// Run `body` `times` times over
+    Repeat { times: u64, body: Vec<Instruction> },
+}
+
+/// A word of a program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    /// `L<n>` or `R<n>`
+    Turn { direction: i64, distance: i64 },
+    /// `=<n>`
+    Set(i64),
+    /// `x<n>{`, opening a block repeated n times
+    Open(u64),
+    /// `}`
+    Close,
+}
+
+/// Parse a single word: `L<n>`, `R<n>`, `=<n>` or `x<n>`
+fn parse_word(line_no: usize, line: &str, word: &str) -> Result<Token, ParseError> {
+    let mut chars = word.chars();
+    let first = chars.next();
+    let number = chars.as_str();
+    match first {
+        Some(kind @ ('L' | 'R')) => {
+            let distance: i64 = parse_number(line_no, line, number)?;
+            if distance < 0 {
+                return Err(ParseError::at(line_no, line, number, "rotation distance must not be negative"));
+            }
+            let direction = if kind == 'L' { -1 } else { 1 };
+            Ok(Token::Turn { direction, distance })
+        }
+        Some('=') => Ok(Token::Set(parse_number(line_no, line, number)?)),
+        Some('x') => Ok(Token::Open(parse_number(line_no, line, number)?)),
+        _ => Err(ParseError::at(
+            line_no,
+            line,
+            word,
+            "expected an instruction starting with 'L', 'R', '=' or 'x'",
+        )),
+    }
+}
 
-use std::fs;
+/// Split a program into tokens, each with its line and column
+///
+/// Words are separated by whitespace or braces, and `#` comments out the
+/// rest of its line. A repeat's `{` must follow its count on the same line.
+/// When `lenient`, words that are not instructions are skipped.
+fn tokenize(input: &str, lenient: bool) -> Result<Vec<(Token, usize, usize)>, ParseError> {
+    let mut tokens = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        let line_no = idx + 1;
+        let mut rest = line.split('#').next().unwrap_or_default();
+        loop {
+            rest = rest.trim_start();
+            let Some(first) = rest.chars().next() else {
+                break;
+            };
+            let column = column_of(line, rest);
+            let token = if first == '}' {
+                rest = &rest[1..];
+                Ok(Token::Close)
+            } else if first == '{' {
+                let brace = &rest[..1];
+                rest = &rest[1..];
+                Err(ParseError::at(line_no, line, brace, "'{' must follow a repeat count like 'x3'"))
+            } else {
+                let end = rest
+                    .find(|ch: char| ch.is_whitespace() || ch == '{' || ch == '}')
+                    .unwrap_or(rest.len());
+                let (word, after) = rest.split_at(end);
+                rest = after;
+                match parse_word(line_no, line, word) {
+                    Ok(Token::Open(times)) => match rest.trim_start().strip_prefix('{') {
+                        Some(after) => {
+                            rest = after;
+                            Ok(Token::Open(times))
+                        }
+                        None => Err(ParseError::at(line_no, line, word, format!("expected '{{' after '{}'", word))),
+                    },
+                    other => other,
+                }
+            };
+            match token {
+                Ok(token) => tokens.push((token, line_no, column)),
+                Err(_) if lenient => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+    Ok(tokens)
+}
 
-/// Parse a single rotation instruction (e.g., "L68" or "R48")
-/// Returns (direction, distance) where direction is -1 for L and 1 for R
-fn parse_rotation(s: &str) -> (i64, i64) {
-    let s = s.trim();
-    if s.is_empty() {
-        return (0, 0);
+/// Parse a program, matching each `}` with the repeat it closes
+///
+/// When `lenient`, a stray `}` is skipped and blocks left open are closed
+/// at the end of the input.
+fn parse_program(input: &str, lenient: bool) -> Result<Vec<Instruction>, ParseError> {
+    // Blocks still open: repeat count, where it opened, and the enclosing body
+    let mut open: Vec<(u64, usize, usize, Vec<Instruction>)> = Vec::new();
+    let mut program = Vec::new();
+    for (token, line, column) in tokenize(input, lenient)? {
+        let instruction = match token {
+            Token::Turn { direction, distance } => Instruction::Turn { direction, distance },
+            Token::Set(position) => Instruction::Set(position),
+            Token::Open(times) => {
+                open.push((times, line, column, std::mem::take(&mut program)));
+                continue;
+            }
+            Token::Close => match open.pop() {
+                Some((times, _, _, outer)) => {
+                    let body = std::mem::replace(&mut program, outer);
+                    Instruction::Repeat { times, body }
+                }
+                None if lenient => continue,
+                None => return Err(ParseError::new(line, column, "'}' does not close a repeat block")),
+            },
+        };
+        program.push(instruction);
     }
-    let direction = if s.starts_with('L') { -1 } else { 1 };
-    let distance: i64 = s[1..].parse().unwrap_or(0);
-    (direction, distance)
+    while let Some((times, line, column, outer)) = open.pop() {
+        if !lenient {
+            return Err(ParseError::new(line, column, format!("repeat block 'x{}' is never closed", times)));
+        }
+        let body = std::mem::replace(&mut program, outer);
+        program.push(Instruction::Repeat { times, body });
+    }
+    Ok(program)
+}
+
+/// Parse a program, reporting the first bad word
+fn parse_input(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse_program(input, false)
+}
+
+/// Parse a program, skipping words that aren't valid instructions
+fn parse_input_lenient(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse_program(input, true)
 }
 
-/// Apply a rotation to the current position
-/// Returns the new position (0-99)
-fn apply_rotation(position: i64, direction: i64, distance: i64) -> i64 {
-    let new_pos = position + direction * distance;
-    // Handle wrapping: mod 100, but handle negative numbers
-    ((new_pos % 100) + 100) % 100
+/// Apply a rotation to the current position on a dial of `modulus` positions
+/// Returns the new position (0 to modulus - 1)
+fn apply_rotation(position: i64, direction: i64, distance: i64, modulus: i64) -> i64 {
+    // Widened so that distances near i64::MAX can't overflow
+    let moved = i128::from(position) + i128::from(direction) * i128::from(distance);
+    moved.rem_euclid(i128::from(modulus)) as i64
 }
 
-/// Count how many times the dial passes through 0 during a rotation
-/// This includes every click that lands on 0 (during and at the end)
-fn count_zeros_during_rotation(start: i64, direction: i64, distance: i64) -> i64 {
+/// Count how many clicks of a rotation land on `target`, on a dial of
+/// `modulus` positions
+/// This includes every click that lands on it (during and at the end)
+fn count_hits_during_rotation(start: i64, direction: i64, distance: i64, target: i64, modulus: i64) -> i64 {
     if distance == 0 {
         return 0;
     }
 
     // For a rotation, we visit positions: start+dir, start+2*dir, ..., start+D*dir
-    // We need to count how many of these (in raw form, before mod) are multiples of 100
-
-    if direction == -1 {
-        // Left rotation: range [start-distance, start-1]
-        // Count multiples of 100 in this range
-        let a = start - distance;
-        let b = start - 1;
-        // floor(b/100) - floor((a-1)/100) handles negative numbers correctly with integer division
-        floor_div(b, 100) - floor_div(a - 1, 100)
-    } else {
-        // Right rotation: range [start+1, start+distance]
-        let a = start + 1;
-        let b = start + distance;
-        floor_div(b, 100) - floor_div(a - 1, 100)
+    // Shifting by the target, we count how many of these are multiples of the modulus.
+    // A right turn is mirrored into a left one so the range below can't overflow.
+    let offset = start.rem_euclid(modulus) - target.rem_euclid(modulus);
+    let start = if direction == -1 { offset } else { -offset }.rem_euclid(modulus);
+    // Left rotation: range [start-distance, start-1]
+    let (a, b) = (start - distance, start - 1);
+    // floor(b/m) - floor((a-1)/m) handles negative numbers correctly
+    floor_div(b, modulus) - floor_div(a - 1, modulus)
+}
+
+/// Something that happened while the dial turned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialEvent {
+    /// Rotation `step` (counting from 0) made `count` full revolutions
+    Revolutions { step: usize, count: i64 },
+    /// Rotation `step` clicked past `target` `count` times before it ended
+    Passed { step: usize, target: i64, count: i64 },
+    /// Rotation `step` ended pointing at `target`, even if it didn't move
+    Landed { step: usize, target: i64 },
+}
+
+/// Totals from running a program on a dial
+///
+/// Totals saturate at `i128::MAX` instead of overflowing, so a total nobody
+/// asks for can't make a run fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Tally {
+    /// Turns made
+    pub turns: i128,
+    /// Turns that ended pointing at a target, moving or not
+    pub landed: i128,
+    /// Clicks onto a target, during turns or at their end, so a turn of
+    /// distance 0 never clicks
+    pub clicks: i128,
+}
+
+impl Tally {
+    /// Both tallies together, saturating each total
+    pub fn saturating_add(self, other: Tally) -> Tally {
+        Tally {
+            turns: self.turns.saturating_add(other.turns),
+            landed: self.landed.saturating_add(other.landed),
+            clicks: self.clicks.saturating_add(other.clicks),
+        }
     }
 }
 
-/// Integer floor division that handles negative numbers correctly
-fn floor_div(a: i64, b: i64) -> i64 {
-    if a >= 0 {
-        a / b
-    } else {
-        (a - b + 1) / b
+/// Why a part's total could not be reported
+const OVERFLOW: &str = "program totals overflow 64 bits";
+
+/// Largest dial whose repeat blocks [`Dial::run`] will tabulate
+const MAX_TABULATED: i64 = 1 << 16;
+
+/// What running a block does from each start position of a dial
+#[derive(Debug, Clone)]
+struct Table {
+    /// Where a run from each start ends
+    ends: Vec<i64>,
+    /// What a run from each start counts
+    tallies: Vec<Tally>,
+}
+
+impl Table {
+    /// Running nothing on a dial of `modulus` positions
+    fn identity(modulus: usize) -> Self {
+        Table {
+            ends: (0..modulus as i64).collect(),
+            tallies: vec![Tally::default(); modulus],
+        }
+    }
+
+    /// Running this block and then `next`
+    fn then(&self, next: &Table) -> Table {
+        let (ends, tallies) = self
+            .ends
+            .iter()
+            .zip(&self.tallies)
+            .map(|(&end, &tally)| {
+                let end = end as usize;
+                (next.ends[end], tally.saturating_add(next.tallies[end]))
+            })
+            .unzip();
+        Table { ends, tallies }
+    }
+
+    /// Running this block `times` times over, by repeated doubling
+    fn repeated(&self, mut times: u64) -> Table {
+        let mut result = Table::identity(self.ends.len());
+        let mut power = self.clone();
+        while times > 0 {
+            if times & 1 == 1 {
+                result = result.then(&power);
+            }
+            times >>= 1;
+            if times > 0 {
+                power = power.then(&power);
+            }
+        }
+        result
     }
 }
 
-/// Part 1: Count how many times the dial points at 0 after any rotation
-pub fn part1(input: &str) -> i64 {
-    let mut position: i64 = 50; // Dial starts at 50
-    let mut zero_count = 0;
-
-    for line in input.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
+/// A dial with `modulus` positions that reports when it meets its targets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dial {
+    modulus: i64,
+    position: i64,
+    targets: Vec<i64>,
+    steps: usize,
+}
+
+impl Dial {
+    /// A dial of `modulus` positions pointing at `start`, watching `targets`
+    ///
+    /// Positions wrap, so a start or target outside `0..modulus` is reduced,
+    /// and repeated targets are only watched once. Panics if `modulus` is not
+    /// positive.
+    pub fn new(modulus: i64, start: i64, targets: impl IntoIterator<Item = i64>) -> Self {
+        assert!(modulus > 0, "a dial needs at least one position");
+        let mut targets: Vec<i64> = targets.into_iter().map(|t| t.rem_euclid(modulus)).collect();
+        targets.sort_unstable();
+        targets.dedup();
+        Dial {
+            modulus,
+            position: start.rem_euclid(modulus),
+            targets,
+            steps: 0,
+        }
+    }
+
+    /// The puzzle's dial: 100 positions, starting at 50, watching 0
+    pub fn safe() -> Self {
+        Dial::new(100, 50, [0])
+    }
+
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    pub fn modulus(&self) -> i64 {
+        self.modulus
+    }
+
+    /// Number of rotations applied so far
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Turn the dial `distance` clicks (left for direction -1, right for 1),
+    /// passing each event to `on_event`
+    ///
+    /// Events come in the order revolutions, passes, landings, and only when
+    /// their count is non-zero. Passes and landings cover every target, in
+    /// ascending order. A turn that ends on a target lands on it whatever its
+    /// distance, but only clicks onto it if it moved.
+    pub fn rotate(&mut self, direction: i64, distance: i64, mut on_event: impl FnMut(DialEvent)) {
+        let step = self.steps;
+        let end = apply_rotation(self.position, direction, distance, self.modulus);
+
+        let count = distance / self.modulus;
+        if count > 0 {
+            on_event(DialEvent::Revolutions { step, count });
+        }
+        for &target in &self.targets {
+            let hits = count_hits_during_rotation(self.position, direction, distance, target, self.modulus);
+            // The click that ends the turn is its landing, not a pass
+            let count = hits - i64::from(distance > 0 && end == target);
+            if count > 0 {
+                on_event(DialEvent::Passed { step, target, count });
+            }
         }
-        let (direction, distance) = parse_rotation(line);
-        position = apply_rotation(position, direction, distance);
-        if position == 0 {
-            zero_count += 1;
+        if self.targets.binary_search(&end).is_ok() {
+            on_event(DialEvent::Landed { step, target: end });
         }
+
+        self.position = end;
+        self.steps += 1;
+    }
+
+    /// Point the dial at `position` without turning it
+    pub fn set_position(&mut self, position: i64) {
+        self.position = position.rem_euclid(self.modulus);
+    }
+
+    /// Where a turn from `start` ends and what it counts, leaving the dial be
+    fn turn_from(&self, start: i64, direction: i64, distance: i64) -> (i64, Tally) {
+        let end = apply_rotation(start, direction, distance, self.modulus);
+        let clicks = self
+            .targets
+            .iter()
+            .map(|&target| i128::from(count_hits_during_rotation(start, direction, distance, target, self.modulus)))
+            .sum();
+        let landed = i128::from(self.targets.binary_search(&end).is_ok());
+        (end, Tally { turns: 1, landed, clicks })
     }
 
-    zero_count
+    /// Run a program, totalling what happens at every target
+    ///
+    /// Each turn is counted in closed form whatever its distance. A repeat
+    /// block's body is tabulated once for every start position and repeated
+    /// by doubling, so a program costs O(instructions × modulus × log times)
+    /// however deeply its blocks nest. Fails if the dial is too large to
+    /// tabulate a repeat block on.
+    pub fn run(&mut self, program: &[Instruction]) -> Result<Tally, String> {
+        let mut tally = Tally::default();
+        for instruction in program {
+            let step = match instruction {
+                &Instruction::Turn { direction, distance } => {
+                    let (end, step) = self.turn_from(self.position, direction, distance);
+                    self.position = end;
+                    step
+                }
+                &Instruction::Set(position) => {
+                    self.set_position(position);
+                    continue;
+                }
+                Instruction::Repeat { times, body } => {
+                    if self.modulus > MAX_TABULATED {
+                        return Err(format!(
+                            "repeat blocks need a dial of at most {} positions, found {}",
+                            MAX_TABULATED, self.modulus
+                        ));
+                    }
+                    let table = self.tabulate(body).repeated(*times);
+                    let start = self.position as usize;
+                    self.position = table.ends[start];
+                    table.tallies[start]
+                }
+            };
+            tally = tally.saturating_add(step);
+        }
+        let turns = usize::try_from(tally.turns).unwrap_or(usize::MAX);
+        self.steps = self.steps.saturating_add(turns);
+        Ok(tally)
+    }
+
+    /// What one run of `program` does from every start position
+    fn tabulate(&self, program: &[Instruction]) -> Table {
+        let mut table = Table::identity(self.modulus as usize);
+        for instruction in program {
+            match instruction {
+                &Instruction::Turn { direction, distance } => {
+                    for (end, tally) in table.ends.iter_mut().zip(&mut table.tallies) {
+                        let (next, step) = self.turn_from(*end, direction, distance);
+                        *end = next;
+                        *tally = tally.saturating_add(step);
+                    }
+                }
+                &Instruction::Set(position) => table.ends.fill(position.rem_euclid(self.modulus)),
+                Instruction::Repeat { times, body } => table = table.then(&self.tabulate(body).repeated(*times)),
+            }
+        }
+        table
+    }
+
+    /// Every event from running `program`, as it happens
+    ///
+    /// Repeat blocks are expanded one pass at a time as the events are
+    /// consumed; [`Dial::run`] totals a program without visiting each turn.
+    pub fn events(self, program: &[Instruction]) -> Events<'_> {
+        let top = Block {
+            body: program,
+            next: 0,
+            passes: 1,
+            idle: false,
+            first: true,
+            mark: 0,
+        };
+        Events {
+            dial: self,
+            blocks: vec![top],
+            pending: VecDeque::new(),
+            emitted: 0,
+        }
+    }
 }
 
-/// Part 2: Count all times the dial passes through 0 during any rotation
-pub fn part2(input: &str) -> i64 {
-    let mut position: i64 = 50; // Dial starts at 50
-    let mut zero_count = 0;
-
-    for line in input.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
+/// Whether running `program` can click the dial at all
+///
+/// A body that can't starts each pass after its first where the one before
+/// ended, so those passes all repeat the same events.
+fn has_clicks(program: &[Instruction]) -> bool {
+    program.iter().any(|instruction| match instruction {
+        Instruction::Turn { distance, .. } => *distance > 0,
+        Instruction::Set(_) => false,
+        Instruction::Repeat { times, body } => *times > 0 && has_clicks(body),
+    })
+}
+
+/// Number of turns running `program` makes, saturating at `u64::MAX`
+fn turn_count(program: &[Instruction]) -> u64 {
+    program.iter().fold(0u64, |count, instruction| {
+        count.saturating_add(match instruction {
+            Instruction::Turn { .. } => 1,
+            Instruction::Set(_) => 0,
+            Instruction::Repeat { times, body } => times.saturating_mul(turn_count(body)),
+        })
+    })
+}
+
+/// The events of a program, from [`Dial::events`]
+pub struct Events<'a> {
+    dial: Dial,
+    /// Blocks being run, innermost last
+    blocks: Vec<Block<'a>>,
+    /// Events from the last turn not yet returned
+    pending: VecDeque<DialEvent>,
+    /// Events produced so far
+    emitted: u64,
+}
+
+/// A repeat block part way through its passes
+struct Block<'a> {
+    body: &'a [Instruction],
+    /// Index of the next instruction in `body`
+    next: usize,
+    /// Passes left, including the current one
+    passes: u64,
+    /// Whether the body can't click, so a silent pass after the first means
+    /// every later pass is silent too
+    idle: bool,
+    /// Whether the current pass is the first
+    first: bool,
+    /// `emitted` when the current pass began
+    mark: u64,
+}
+
+impl Iterator for Events<'_> {
+    type Item = DialEvent;
+
+    fn next(&mut self) -> Option<DialEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            let block = self.blocks.last_mut()?;
+            let Some(instruction) = block.body.get(block.next) else {
+                block.passes -= 1;
+                if block.idle && !block.first && self.emitted == block.mark {
+                    // The rest are silent too, and only count towards the
+                    // step numbers
+                    let skipped = turn_count(block.body).saturating_mul(block.passes);
+                    let skipped = usize::try_from(skipped).unwrap_or(usize::MAX);
+                    self.dial.steps = self.dial.steps.saturating_add(skipped);
+                    block.passes = 0;
+                }
+                if block.passes == 0 {
+                    self.blocks.pop();
+                } else {
+                    block.next = 0;
+                    block.first = false;
+                    block.mark = self.emitted;
+                }
+                continue;
+            };
+            block.next += 1;
+            match instruction {
+                &Instruction::Turn { direction, distance } => {
+                    let (pending, emitted) = (&mut self.pending, &mut self.emitted);
+                    self.dial.rotate(direction, distance, |event| {
+                        pending.push_back(event);
+                        *emitted += 1;
+                    });
+                }
+                &Instruction::Set(position) => self.dial.set_position(position),
+                Instruction::Repeat { times, body } => {
+                    if *times > 0 {
+                        self.blocks.push(Block {
+                            body,
+                            next: 0,
+                            passes: *times,
+                            idle: !has_clicks(body),
+                            first: true,
+                            mark: self.emitted,
+                        });
+                    }
+                }
+            }
         }
-        let (direction, distance) = parse_rotation(line);
-        zero_count += count_zeros_during_rotation(position, direction, distance);
-        position = apply_rotation(position, direction, distance);
     }
+}
 
-    zero_count
+/// Part 1: Count how many times the dial points at 0 after any rotation
+pub fn part1(program: &[Instruction]) -> Result<i64, String> {
+    let landed = Dial::safe().run(program)?.landed;
+    i64::try_from(landed).map_err(|_| OVERFLOW.to_string())
+}
+
+/// Part 2: Count all times the dial passes through 0 during any rotation
+pub fn part2(program: &[Instruction]) -> Result<i64, String> {
+    let clicks = Dial::safe().run(program)?.clicks;
+    i64::try_from(clicks).map_err(|_| OVERFLOW.to_string())
 }
 
-/// Entry point for running Day 1 solutions
-pub fn run() {
-    let input = fs::read_to_string("puzzles/day01/input.txt")
-        .expect("Failed to read input file");
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Secret Entrance";
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn parse_lenient(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input_lenient(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, String> {
+        part1(input).map(Answer::from)
+    }
 
-    println!("Day 1: Secret Entrance");
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    fn part2(input: &Self::Input) -> Result<Answer, String> {
+        part2(input).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Parse a line holding a single turn into (direction, distance)
+    fn parse_rotation(line_no: usize, line: &str) -> Result<(i64, i64), ParseError> {
+        match parse_word(line_no, line, line.trim())? {
+            Token::Turn { direction, distance } => Ok((direction, distance)),
+            other => panic!("expected a turn, found {:?}", other),
+        }
+    }
+
+    fn turn(direction: i64, distance: i64) -> Instruction {
+        Instruction::Turn { direction, distance }
+    }
+
     // Example input from the puzzle description
     const EXAMPLE_INPUT: &str = "L68
 L30
@@ -125,46 +627,69 @@ L82";
 
     #[test]
     fn test_parse_rotation_left() {
-        assert_eq!(parse_rotation("L68"), (-1, 68));
-        assert_eq!(parse_rotation("L5"), (-1, 5));
+        assert_eq!(parse_rotation(1, "L68"), Ok((-1, 68)));
+        assert_eq!(parse_rotation(1, "L5"), Ok((-1, 5)));
     }
 
     #[test]
     fn test_parse_rotation_right() {
-        assert_eq!(parse_rotation("R48"), (1, 48));
-        assert_eq!(parse_rotation("R14"), (1, 14));
+        assert_eq!(parse_rotation(1, "R48"), Ok((1, 48)));
+        assert_eq!(parse_rotation(1, "R14"), Ok((1, 14)));
+    }
+
+    #[test]
+    fn test_parse_rotation_errors() {
+        assert_eq!(
+            parse_rotation(4, "X12"),
+            Err(ParseError::new(4, 1, "expected an instruction starting with 'L', 'R', '=' or 'x'"))
+        );
+        assert_eq!(
+            parse_rotation(2, "  L1x"),
+            Err(ParseError::new(2, 4, "invalid number '1x'"))
+        );
+    }
+
+    #[test]
+    fn test_parse_input_reports_bad_line() {
+        let err = parse_input("L1\n\nR2\nbogus\n").unwrap_err();
+        assert_eq!(err.line, 4);
+    }
+
+    #[test]
+    fn test_parse_input_lenient_skips_bad_lines() {
+        assert_eq!(parse_input_lenient("L1\nbogus\nR2"), Ok(vec![turn(-1, 1), turn(1, 2)]));
     }
 
     #[test]
     fn test_apply_rotation_simple() {
         // From 50, L68 should go to 82 (50 - 68 = -18, wraps to 82)
-        assert_eq!(apply_rotation(50, -1, 68), 82);
+        assert_eq!(apply_rotation(50, -1, 68, 100), 82);
         // From 82, L30 should go to 52
-        assert_eq!(apply_rotation(82, -1, 30), 52);
+        assert_eq!(apply_rotation(82, -1, 30, 100), 52);
         // From 52, R48 should go to 0 (52 + 48 = 100, wraps to 0)
-        assert_eq!(apply_rotation(52, 1, 48), 0);
+        assert_eq!(apply_rotation(52, 1, 48, 100), 0);
     }
 
     #[test]
     fn test_apply_rotation_wrap_left() {
         // From 5, L10 should go to 95
-        assert_eq!(apply_rotation(5, -1, 10), 95);
+        assert_eq!(apply_rotation(5, -1, 10, 100), 95);
         // From 0, L1 should go to 99
-        assert_eq!(apply_rotation(0, -1, 1), 99);
+        assert_eq!(apply_rotation(0, -1, 1, 100), 99);
     }
 
     #[test]
     fn test_apply_rotation_wrap_right() {
         // From 99, R1 should go to 0
-        assert_eq!(apply_rotation(99, 1, 1), 0);
+        assert_eq!(apply_rotation(99, 1, 1, 100), 0);
         // From 95, R10 should go to 5
-        assert_eq!(apply_rotation(95, 1, 10), 5);
+        assert_eq!(apply_rotation(95, 1, 10, 100), 5);
     }
 
     #[test]
     fn test_part1_example() {
         // From puzzle: dial points at 0 three times at end of rotations
-        let result = part1(EXAMPLE_INPUT);
+        let result = part1(&parse_input(EXAMPLE_INPUT).unwrap()).unwrap();
         assert_eq!(result, 3, "Part 1: Should count 3 times at position 0");
     }
 
@@ -186,8 +711,8 @@ L82";
         ];
 
         for (instruction, expected) in rotations {
-            let (direction, distance) = parse_rotation(instruction);
-            position = apply_rotation(position, direction, distance);
+            let (direction, distance) = parse_rotation(1, instruction).unwrap();
+            position = apply_rotation(position, direction, distance, 100);
             assert_eq!(position, expected, "After {} should be at {}", instruction, expected);
         }
     }
@@ -196,14 +721,14 @@ L82";
     fn test_part1_single_zero() {
         // R50 from 50 should land on 0
         let input = "R50";
-        assert_eq!(part1(input), 1);
+        assert_eq!(part1(&parse_input(input).unwrap()), Ok(1));
     }
 
     #[test]
     fn test_part1_no_zeros() {
         // L1 from 50 should land on 49
         let input = "L1";
-        assert_eq!(part1(input), 0);
+        assert_eq!(part1(&parse_input(input).unwrap()), Ok(0));
     }
 
     // Part 2 tests
@@ -211,62 +736,62 @@ L82";
     #[test]
     fn test_count_zeros_left_passes_zero() {
         // From 50, L68 passes through 0 once (at position 0)
-        assert_eq!(count_zeros_during_rotation(50, -1, 68), 1);
+        assert_eq!(count_hits_during_rotation(50, -1, 68, 0, 100), 1);
     }
 
     #[test]
     fn test_count_zeros_left_no_pass() {
         // From 82, L30 doesn't pass through 0
-        assert_eq!(count_zeros_during_rotation(82, -1, 30), 0);
+        assert_eq!(count_hits_during_rotation(82, -1, 30, 0, 100), 0);
     }
 
     #[test]
     fn test_count_zeros_right_lands_on_zero() {
         // From 52, R48 lands on 0 (passes through 100, which is 0)
-        assert_eq!(count_zeros_during_rotation(52, 1, 48), 1);
+        assert_eq!(count_hits_during_rotation(52, 1, 48, 0, 100), 1);
     }
 
     #[test]
     fn test_count_zeros_right_passes_zero() {
         // From 95, R60 passes through 0 once (at 100)
-        assert_eq!(count_zeros_during_rotation(95, 1, 60), 1);
+        assert_eq!(count_hits_during_rotation(95, 1, 60, 0, 100), 1);
     }
 
     #[test]
     fn test_count_zeros_left_lands_on_zero() {
         // From 55, L55 lands on 0
-        assert_eq!(count_zeros_during_rotation(55, -1, 55), 1);
+        assert_eq!(count_hits_during_rotation(55, -1, 55, 0, 100), 1);
     }
 
     #[test]
     fn test_count_zeros_left_from_zero() {
         // From 0, L1 goes to 99, doesn't pass 0 (starts at 0, first click is 99)
-        assert_eq!(count_zeros_during_rotation(0, -1, 1), 0);
+        assert_eq!(count_hits_during_rotation(0, -1, 1, 0, 100), 0);
     }
 
     #[test]
     fn test_count_zeros_left_wraps_to_zero() {
         // From 99, L99 lands on 0
-        assert_eq!(count_zeros_during_rotation(99, -1, 99), 1);
+        assert_eq!(count_hits_during_rotation(99, -1, 99, 0, 100), 1);
     }
 
     #[test]
     fn test_count_zeros_right_1000() {
         // From 50, R1000 should pass through 0 ten times
         // Positions: 51, 52, ..., 100 (1st zero), ..., 200 (2nd), ..., 1000 (10th), then ends at 50
-        assert_eq!(count_zeros_during_rotation(50, 1, 1000), 10);
+        assert_eq!(count_hits_during_rotation(50, 1, 1000, 0, 100), 10);
     }
 
     #[test]
     fn test_count_zeros_left_during_82() {
         // From 14, L82 passes through 0 once
-        assert_eq!(count_zeros_during_rotation(14, -1, 82), 1);
+        assert_eq!(count_hits_during_rotation(14, -1, 82, 0, 100), 1);
     }
 
     #[test]
     fn test_part2_example() {
         // From puzzle: 3 at end of rotation + 3 during rotations = 6
-        let result = part2(EXAMPLE_INPUT);
+        let result = part2(&parse_input(EXAMPLE_INPUT).unwrap()).unwrap();
         assert_eq!(result, 6, "Part 2: Should count 6 total times at position 0");
     }
 
@@ -274,7 +799,227 @@ L82";
     fn test_part2_single_large_rotation() {
         // R1000 from 50 should pass 0 ten times
         let input = "R1000";
-        assert_eq!(part2(input), 10);
+        assert_eq!(part2(&parse_input(input).unwrap()), Ok(10));
+    }
+
+    #[test]
+    fn test_dial_events() {
+        let mut dial = Dial::new(10, 7, [0, 5, 25]);
+        let mut events = Vec::new();
+        dial.rotate(1, 28, |event| events.push(event));
+        // 7 -> 35 clicks past 10, 15, 20, 25, 30 and ends on 35 (5)
+        assert_eq!(
+            events,
+            [
+                DialEvent::Revolutions { step: 0, count: 2 },
+                DialEvent::Passed { step: 0, target: 0, count: 3 },
+                DialEvent::Passed { step: 0, target: 5, count: 2 },
+                DialEvent::Landed { step: 0, target: 5 },
+            ]
+        );
+        assert_eq!((dial.position(), dial.steps()), (5, 1));
+
+        events.clear();
+        dial.rotate(-1, 0, |event| events.push(event));
+        assert_eq!(events, [DialEvent::Landed { step: 1, target: 5 }], "standing still on a target lands on it");
+    }
+
+    #[test]
+    fn test_zero_turn_on_target() {
+        // R0 while pointing at 0 lands again without clicking
+        let program = parse_input("R50\nR0").unwrap();
+        assert_eq!(part1(&program), Ok(2));
+        assert_eq!(part2(&program), Ok(1));
+    }
+
+    #[test]
+    fn test_dial_events_match_clicks() {
+        // Count hits click by click on a small dial
+        let rotations = [(-1, 13), (1, 4), (1, 0), (-1, 2), (1, 21), (-1, 7)];
+        let program: Vec<Instruction> = rotations.iter().map(|&(direction, distance)| turn(direction, distance)).collect();
+        let (modulus, start, targets) = (6, 4, [1, 3]);
+        let events: Vec<DialEvent> = Dial::new(modulus, start, targets).events(&program).collect();
+        let mut position = start;
+        for (step, &(direction, distance)) in rotations.iter().enumerate() {
+            let mut hits = [0; 2];
+            for _ in 0..distance {
+                position = apply_rotation(position, direction, 1, modulus);
+                for (hit, target) in hits.iter_mut().zip(targets) {
+                    *hit += i64::from(position == target);
+                }
+            }
+            for (hit, target) in hits.into_iter().zip(targets) {
+                let reported: i64 = events
+                    .iter()
+                    .map(|&event| match event {
+                        DialEvent::Passed { step: s, target: t, count } if (s, t) == (step, target) => count,
+                        DialEvent::Landed { step: s, target: t } if (s, t) == (step, target) => i64::from(distance > 0),
+                        _ => 0,
+                    })
+                    .sum();
+                assert_eq!(reported, hit, "step {} target {}", step, target);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_program() {
+        let input = "x3{ L5 =10 # comment }\n  R2 x2 {R1}}\n# R7\n=-3";
+        assert_eq!(
+            tokenize(input, false).unwrap()[..3],
+            [
+                (Token::Open(3), 1, 1),
+                (Token::Turn { direction: -1, distance: 5 }, 1, 5),
+                (Token::Set(10), 1, 8),
+            ]
+        );
+        let inner = Instruction::Repeat { times: 2, body: vec![turn(1, 1)] };
+        assert_eq!(
+            parse_input(input),
+            Ok(vec![
+                Instruction::Repeat { times: 3, body: vec![turn(-1, 5), Instruction::Set(10), turn(1, 2), inner] },
+                Instruction::Set(-3),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_program_errors() {
+        let cases = [
+            ("L1\nx3 L2", ParseError::new(2, 1, "expected '{' after 'x3'")),
+            ("R1 {L2}", ParseError::new(1, 4, "'{' must follow a repeat count like 'x3'")),
+            ("x2{R1}\n }", ParseError::new(2, 2, "'}' does not close a repeat block")),
+            ("x2{ x5{ R1 }", ParseError::new(1, 1, "repeat block 'x2' is never closed")),
+            ("x-1{R1}", ParseError::new(1, 2, "invalid number '-1'")),
+            ("=5 =a", ParseError::new(1, 5, "invalid number 'a'")),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_input(input), Err(expected), "for {:?}", input);
+        }
+        assert_eq!(
+            parse_input_lenient("x2{R1 bogus\n} } x3{L4"),
+            Ok(vec![
+                Instruction::Repeat { times: 2, body: vec![turn(1, 1)] },
+                Instruction::Repeat { times: 3, body: vec![turn(-1, 4)] },
+            ])
+        );
+    }
+
+    /// The program with every repeat written out
+    fn unroll(program: &[Instruction]) -> Vec<Instruction> {
+        let mut flat = Vec::new();
+        for instruction in program {
+            match instruction {
+                Instruction::Repeat { times, body } => {
+                    for _ in 0..*times {
+                        flat.extend(unroll(body));
+                    }
+                }
+                other => flat.push(other.clone()),
+            }
+        }
+        flat
+    }
+
+    #[test]
+    fn test_repeat_matches_unrolled() {
+        let programs = [
+            "x10{R3}",
+            "x9{L4 R13}",
+            "x7{=2 R5 L1}",
+            "L2 x25{R1 x4{L3 =5} R9 x0{R1}} x3{}",
+            "x20{ x11{R2} L30 } R4",
+            "x2{ x3{ x2{ x3{ x2{ R1 =4 L2 R0 } } L9 } } }",
+        ];
+        for input in programs {
+            let program = parse_input(input).unwrap();
+            let mut dial = Dial::new(7, 3, [0, 4]);
+            let mut unrolled = dial.clone();
+            assert_eq!(dial.run(&program), unrolled.run(&unroll(&program)), "for {:?}", input);
+            assert_eq!(dial, unrolled, "for {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_huge_repeat() {
+        // A billion single clicks are one long turn
+        let clicks = parse_input("x1000000000{R1}").unwrap();
+        let mut dial = Dial::safe();
+        let tally = dial.run(&clicks).unwrap();
+        assert_eq!(tally, Tally { turns: 1_000_000_000, landed: 10_000_000, clicks: 10_000_000 });
+        assert_eq!(dial.position(), 50);
+        assert_eq!(Dial::safe().run(&[turn(1, 1_000_000_000)]).unwrap().clicks, tally.clicks);
+
+        let nested = parse_input("x1000000{ x1000000{ R100 } =0 }").unwrap();
+        assert_eq!(part2(&nested), Ok(1_000_000_000_000));
+        assert_eq!(part1(&nested), Ok(999_999_000_000), "the first pass starts at 50 and never lands");
+    }
+
+    #[test]
+    fn test_huge_nested_repeat_overflows() {
+        // 10^18 clicks still fit in 64 bits, but 10^27 do not
+        let fits = parse_input("x1000000000{x1000000000{R100}}").unwrap();
+        assert_eq!(part2(&fits), Ok(1_000_000_000_000_000_000));
+        let huge = parse_input("x1000000000{x1000000000{x1000000000{R100}}}").unwrap();
+        assert_eq!(part2(&huge), Err(OVERFLOW.to_string()));
+
+        // More turns than fit in an i64 are fine when the answers do
+        let wide = parse_input("x18446744073709551615{R1}").unwrap();
+        assert_eq!(Dial::safe().run(&wide).unwrap().turns, u64::MAX.into());
+        assert_eq!(part1(&wide), Ok(184_467_440_737_095_516));
+        assert_eq!(part2(&wide), Ok(184_467_440_737_095_516));
+        assert_eq!(
+            Day01::part2(&huge),
+            Err("program totals overflow 64 bits".to_string())
+        );
+    }
+
+    #[test]
+    fn test_deep_nesting() {
+        // 2^40 and 3^25 single clicks from 50, without running each pass
+        let deep = format!("{}R1{}", "x2{".repeat(40), "}".repeat(40));
+        let expected = ((1i64 << 40) + 50) / 100;
+        assert_eq!(part1(&parse_input(&deep).unwrap()), Ok(expected));
+        assert_eq!(part2(&parse_input(&deep).unwrap()), Ok(expected));
+
+        let deep = format!("{}R1{}", "x3{".repeat(25), "}".repeat(25));
+        let expected = (3i64.pow(25) + 50) / 100;
+        assert_eq!(part2(&parse_input(&deep).unwrap()), Ok(expected));
+    }
+
+    #[test]
+    fn test_repeat_needs_small_dial() {
+        let program = parse_input("R5 x2{R1}").unwrap();
+        assert_eq!(
+            Dial::new(1 << 20, 0, [0]).run(&program),
+            Err("repeat blocks need a dial of at most 65536 positions, found 1048576".to_string())
+        );
+        assert_eq!(Dial::new(1 << 20, 0, [6]).run(&program[..1]).map(|tally| tally.turns), Ok(1));
+    }
+
+    #[test]
+    fn test_events_expand_repeats() {
+        let program = parse_input("R3 x4{ L7 =2 x2{R5} x1000{} } x0{R1}").unwrap();
+        let dial = Dial::new(6, 1, [0, 4]);
+        let events: Vec<DialEvent> = dial.clone().events(&program).collect();
+        assert_eq!(events, dial.events(&unroll(&program)).collect::<Vec<DialEvent>>());
+        assert_eq!(events.last(), Some(&DialEvent::Landed { step: 12, target: 0 }));
+
+        // Blocks that never click stop once a pass after their first is silent
+        let idle = parse_input("x1000000{ =7 x1000000{R0} } R93").unwrap();
+        let events: Vec<DialEvent> = Dial::safe().events(&idle).collect();
+        assert_eq!(events, [DialEvent::Landed { step: 1_000_000_000_000, target: 0 }]);
+
+        // ...but keep landing if that pass did
+        let standing = parse_input("x1000{ R0 =0 }").unwrap();
+        let events: Vec<DialEvent> = Dial::safe().events(&standing).collect();
+        assert_eq!(events.len(), 999);
+        assert_eq!(events[0], DialEvent::Landed { step: 1, target: 0 });
+
+        let forever = parse_input("x18446744073709551615{R100}").unwrap();
+        let mut endless = Dial::safe().events(&forever);
+        assert_eq!(endless.nth(10), Some(DialEvent::Revolutions { step: 5, count: 1 }));
+        assert_eq!(endless.next(), Some(DialEvent::Passed { step: 5, target: 0, count: 1 }));
     }
 
     #[test]
@@ -286,4 +1031,16 @@ L82";
         assert_eq!(floor_div(-100, 100), -1);
         assert_eq!(floor_div(-101, 100), -2);
     }
+
+    #[test]
+    fn test_huge_distances() {
+        let far = i64::MAX - 7;
+        assert_eq!(apply_rotation(99, 1, far, 100), (99 + far % 100) % 100);
+        assert_eq!(apply_rotation(0, -1, far, 100), (100 - far % 100) % 100);
+        // far is a multiple of 100, so the turn stops 50 short of one more lap
+        assert_eq!(count_hits_during_rotation(50, 1, far, 0, 100), far / 100);
+        assert_eq!(count_hits_during_rotation(3, 1, 10, 0, i64::MAX), 0);
+        assert_eq!(count_hits_during_rotation(3, -1, 10, 0, i64::MAX), 1);
+        assert_eq!(count_hits_during_rotation(3, -1, far, 0, i64::MAX), 1);
+    }
 }
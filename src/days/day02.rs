@@ -1,161 +1,406 @@
-/// Day 2: Gift Shop
+//! Day 2: Gift Shop
+//!
+//! Find invalid product IDs in given ranges. An invalid ID is made only of
+//! some sequence of digits repeated twice (e.g., 55, 6464, 123123).
+//! No leading zeroes allowed.
+//!
+//! Both parts are presets of a `RepetitionRule`, which can also restrict the
+//! repetition counts or change the base.
+//!
+//! Algorithm: closed form per digit length. A repeated-block number is its
+//! seed times a fixed multiplier, so the ones in a range are an arithmetic
+//! series of seeds and no ID is ever visited.
+
+use crate::answer::Answer;
+use crate::error::{parse_number, ParseError};
+use crate::solution::Solution;
+use crate::utils::num::mobius;
+
+/// Which IDs are invalid: those whose digits, written in `base`, are one
+/// block repeated some allowed number of times
 ///
-/// Find invalid product IDs in given ranges. An invalid ID is made only of
-/// some sequence of digits repeated twice (e.g., 55, 6464, 123123).
-/// No leading zeroes allowed.
-
-use std::fs;
-
-/// Check if a number is invalid (made of a pattern repeated exactly twice)
-/// Examples: 11 (1 repeated), 6464 (64 repeated), 123123 (123 repeated)
-/// Not invalid: 101 (has leading zero when split), 111 (odd length)
-fn is_invalid_id(n: i64) -> bool {
-    let s = n.to_string();
-    let len = s.len();
+/// A block never starts with a zero, so 101 is not 01 repeated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepetitionRule {
+    base: u32,
+    min_times: u32,
+    max_times: Option<u32>,
+    allowed: Option<Vec<u32>>,
+}
 
-    // Must have even length to be splittable into two equal parts
-    if len % 2 != 0 {
-        return false;
+impl Default for RepetitionRule {
+    fn default() -> Self {
+        RepetitionRule::at_least(2)
     }
+}
 
-    let half = len / 2;
-    let first_half = &s[..half];
-    let second_half = &s[half..];
+impl RepetitionRule {
+    /// A rule for digits in `base`, repeated at least `min_times` times, at
+    /// most `max_times` if given, and only a count in `allowed` if given
+    /// (e.g. primes)
+    ///
+    /// Fails unless `base` and `min_times` are at least 2 and `max_times` is
+    /// no less than `min_times`, since a single block would match every ID.
+    pub fn new(
+        base: u32,
+        min_times: u32,
+        max_times: Option<u32>,
+        allowed: Option<Vec<u32>>,
+    ) -> Result<Self, String> {
+        if base < 2 {
+            return Err(format!("base must be at least 2, found {}", base));
+        }
+        if min_times < 2 {
+            return Err(format!("a block must repeat at least twice, found {}", min_times));
+        }
+        if let Some(max) = max_times.filter(|&max| max < min_times) {
+            return Err(format!("at most {} repeats is fewer than the minimum of {}", max, min_times));
+        }
+        Ok(RepetitionRule {
+            base,
+            min_times,
+            max_times,
+            allowed,
+        })
+    }
 
-    // Check if both halves are equal
-    if first_half != second_half {
-        return false;
+    /// A block repeated exactly `times` times, in base 10
+    pub fn exactly(times: u32) -> Self {
+        RepetitionRule {
+            max_times: Some(times),
+            ..RepetitionRule::at_least(times)
+        }
     }
 
-    // Check for leading zeros - first half shouldn't start with 0
-    // (unless it's just "0", but then the number would be 00 which is 0, not a valid pattern)
-    if first_half.starts_with('0') {
-        return false;
+    /// A block repeated `times` or more times, in base 10
+    pub fn at_least(times: u32) -> Self {
+        RepetitionRule {
+            base: 10,
+            min_times: times,
+            max_times: None,
+            allowed: None,
+        }
     }
 
-    true
-}
+    fn allows(&self, times: u32) -> bool {
+        times >= self.min_times
+            && self.max_times.is_none_or(|max| times <= max)
+            && self.allowed.as_ref().is_none_or(|allowed| allowed.contains(&times))
+    }
 
-/// Check if a number is invalid for Part 2 (made of a pattern repeated at least twice)
-/// Examples: 11 (1x2), 111 (1x3), 6464 (64x2), 123123 (123x2), 123123123 (123x3)
-fn is_invalid_id_v2(n: i64) -> bool {
-    let s = n.to_string();
-    let len = s.len();
+    /// Block lengths that tile `len` digits an allowed number of times
+    fn blocks(&self, len: u32) -> Vec<u32> {
+        (1..=len)
+            .filter(|&block| len.is_multiple_of(block) && self.allows(len / block))
+            .collect()
+    }
 
-    // Need at least 2 digits to have a repeated pattern
-    if len < 2 {
-        return false;
+    /// How often to count the repeats of each block length so that every
+    /// `len`-digit match is counted once
+    ///
+    /// A number repeating a `d`-digit block also repeats every block length
+    /// that `d` divides, so 111111 is 1×6, 11×3 and 111×2. Each match has a
+    /// shortest block `e`, which divides an allowed block length, and the
+    /// numbers with shortest block exactly `e` are recovered from the
+    /// repeats of each divisor `f` of `e` by Möbius inversion.
+    fn weights(&self, len: u32) -> Vec<(u32, i128)> {
+        let blocks = self.blocks(len);
+        let shortest: Vec<u32> = (1..=len)
+            .filter(|&e| blocks.iter().any(|&d| d.is_multiple_of(e)))
+            .collect();
+        (1..=len)
+            .filter(|&f| len.is_multiple_of(f))
+            .map(|f| {
+                let weight: i128 = shortest
+                    .iter()
+                    .filter(|&&e| e.is_multiple_of(f))
+                    .map(|&e| i128::from(mobius(u64::from(e / f))))
+                    .sum();
+                (f, weight)
+            })
+            .filter(|&(_, weight)| weight != 0)
+            .collect()
     }
 
-    // Try each possible pattern length from 1 to len/2
-    for pattern_len in 1..=len / 2 {
-        // Length must be divisible by pattern length
-        if len % pattern_len != 0 {
-            continue;
+    /// Whether `id` is invalid under this rule
+    pub fn matches(&self, id: i64) -> bool {
+        if id < 1 {
+            return false;
         }
-
-        let repetitions = len / pattern_len;
-        if repetitions < 2 {
-            continue;
+        let mut digits = Vec::new();
+        let mut rest = id;
+        while rest > 0 {
+            digits.push(rest % i64::from(self.base));
+            rest /= i64::from(self.base);
         }
+        let len = digits.len() as u32;
+        self.blocks(len).into_iter().any(|block| {
+            let block = block as usize;
+            digits.iter().skip(block).zip(&digits).all(|(a, b)| a == b)
+        })
+    }
+
+    /// Number of invalid IDs in `start..=end`
+    pub fn count(&self, start: i64, end: i64) -> u64 {
+        let mut count = 0;
+        self.for_each_length(start, end, |lo, hi, len| {
+            for (block, weight) in self.weights(len) {
+                if let Some(seeds) = self.seeds(lo, hi, len, block) {
+                    count += weight * (seeds.last - seeds.first + 1);
+                }
+            }
+        });
+        count as u64
+    }
 
-        let pattern = &s[..pattern_len];
+    /// Sum of the invalid IDs in `start..=end`
+    ///
+    /// Runs in time independent of the width of the range.
+    pub fn sum(&self, start: i64, end: i64) -> i128 {
+        let mut sum = 0;
+        self.for_each_length(start, end, |lo, hi, len| {
+            for (block, weight) in self.weights(len) {
+                if let Some(seeds) = self.seeds(lo, hi, len, block) {
+                    // The repeats are an arithmetic series of seeds
+                    let count = seeds.last - seeds.first + 1;
+                    sum += weight * seeds.multiplier * (seeds.first + seeds.last) * count / 2;
+                }
+            }
+        });
+        sum
+    }
 
-        // Pattern cannot have leading zeros
-        if pattern.starts_with('0') {
-            continue;
-        }
+    /// Every invalid ID in `start..=end`, in ascending order
+    pub fn list(&self, start: i64, end: i64) -> Vec<i64> {
+        let mut ids = Vec::new();
+        self.for_each_length(start, end, |lo, hi, len| {
+            for block in self.blocks(len) {
+                if let Some(seeds) = self.seeds(lo, hi, len, block) {
+                    let repeats = (seeds.first..=seeds.last).map(|seed| seed * seeds.multiplier);
+                    ids.extend(repeats.map(|id| id as i64));
+                }
+            }
+        });
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
 
-        // Check if the entire string is this pattern repeated
-        let mut is_repeated = true;
-        for i in 1..repetitions {
-            let start = i * pattern_len;
-            let end = start + pattern_len;
-            if &s[start..end] != pattern {
-                is_repeated = false;
-                break;
+    /// Split `start..=end` by number of digits, calling `f(lo, hi, len)` for
+    /// each piece
+    fn for_each_length(&self, start: i64, end: i64, mut f: impl FnMut(i128, i128, u32)) {
+        let base = i128::from(self.base);
+        let (start, end) = (i128::from(start.max(1)), i128::from(end));
+        let (mut len, mut lowest) = (1, 1);
+        while lowest <= end {
+            let highest = lowest * base - 1;
+            if highest >= start {
+                f(start.max(lowest), end.min(highest), len);
             }
+            len += 1;
+            lowest *= base;
         }
+    }
 
-        if is_repeated {
-            return true;
-        }
+    /// The seeds whose `block`-digit repeats fill `len` digits and land in
+    /// `lo..=hi`
+    ///
+    /// A repeated number is its seed times (b^len − 1) / (b^block − 1), e.g.
+    /// 12 × 10101 = 121212, so the seeds in range are consecutive.
+    fn seeds(&self, lo: i128, hi: i128, len: u32, block: u32) -> Option<Seeds> {
+        let base = i128::from(self.base);
+        let multiplier = repeat_multiplier(base, block, len / block);
+        // Seeds have exactly `block` digits, so no leading zeros
+        let first = base.pow(block - 1).max((lo + multiplier - 1) / multiplier);
+        let last = (base.pow(block) - 1).min(hi / multiplier);
+        (first <= last).then_some(Seeds {
+            first,
+            last,
+            multiplier,
+        })
+    }
+}
+
+/// A run of consecutive seeds and what to multiply them by to repeat them
+struct Seeds {
+    first: i128,
+    last: i128,
+    multiplier: i128,
+}
+
+/// The multiplier that repeats a `block`-digit seed `times` times in `base`
+fn repeat_multiplier(base: i128, block: u32, times: u32) -> i128 {
+    (base.pow(block * times) - 1) / (base.pow(block) - 1)
+}
+
+/// Parse a single "start-end" range token found on `line`
+fn parse_range(line_no: usize, line: &str, token: &str) -> Result<(i64, i64), ParseError> {
+    let token = token.trim();
+    let (start_str, end_str) = token.split_once('-').ok_or_else(|| {
+        ParseError::at(line_no, line, token, format!("expected a range like '11-22', found '{}'", token))
+    })?;
+    let start: i64 = parse_number(line_no, line, start_str)?;
+    let end: i64 = parse_number(line_no, line, end_str)?;
+    if start > end {
+        return Err(ParseError::at(line_no, line, token, "range start is greater than its end"));
     }
+    Ok((start, end))
+}
 
-    false
+/// Every comma-separated range token, with its line number and line
+fn range_tokens(input: &str) -> impl Iterator<Item = (usize, &str, &str)> {
+    input.lines().enumerate().flat_map(|(idx, line)| {
+        line.split(',')
+            .filter(|token| !token.trim().is_empty())
+            .map(move |token| (idx + 1, line, token))
+    })
 }
 
 /// Parse the input and extract ranges
 /// Input format: "11-22,95-115,998-1012,..."
-fn parse_ranges(input: &str) -> Vec<(i64, i64)> {
-    input
-        .trim()
-        .split(',')
-        .filter_map(|range| {
-            let parts: Vec<&str> = range.trim().split('-').collect();
-            if parts.len() == 2 {
-                let start = parts[0].parse::<i64>().ok()?;
-                let end = parts[1].parse::<i64>().ok()?;
-                Some((start, end))
-            } else {
-                None
-            }
-        })
+fn parse_ranges(input: &str) -> Result<Vec<(i64, i64)>, ParseError> {
+    range_tokens(input)
+        .map(|(line_no, line, token)| parse_range(line_no, line, token))
         .collect()
 }
 
-/// Find all invalid IDs in the given ranges and sum them (Part 1: exactly twice)
-fn sum_invalid_ids(ranges: &[(i64, i64)]) -> i64 {
-    let mut sum = 0;
-    for &(start, end) in ranges {
-        for id in start..=end {
-            if is_invalid_id(id) {
-                sum += id;
-            }
-        }
-    }
-    sum
+/// Parse ranges, skipping any token that isn't a valid range
+fn parse_ranges_lenient(input: &str) -> Vec<(i64, i64)> {
+    range_tokens(input)
+        .filter_map(|(line_no, line, token)| parse_range(line_no, line, token).ok())
+        .collect()
 }
 
-/// Find all invalid IDs in the given ranges and sum them (Part 2: at least twice)
-fn sum_invalid_ids_v2(ranges: &[(i64, i64)]) -> i64 {
-    let mut sum = 0;
-    for &(start, end) in ranges {
-        for id in start..=end {
-            if is_invalid_id_v2(id) {
-                sum += id;
-            }
-        }
-    }
-    sum
+/// Sum the IDs in every range that `rule` marks invalid
+pub fn sum_invalid(rule: &RepetitionRule, ranges: &[(i64, i64)]) -> i128 {
+    ranges.iter().map(|&(start, end)| rule.sum(start, end)).sum()
 }
 
-/// Part 1: Find and sum all invalid product IDs
-pub fn part1(input: &str) -> i64 {
-    let ranges = parse_ranges(input);
-    sum_invalid_ids(&ranges)
+/// Part 1: Find and sum all invalid product IDs (pattern repeated exactly twice)
+pub fn part1(ranges: &[(i64, i64)]) -> i128 {
+    sum_invalid(&RepetitionRule::exactly(2), ranges)
 }
 
 /// Part 2: Find and sum all invalid product IDs (pattern repeated at least twice)
-pub fn part2(input: &str) -> i64 {
-    let ranges = parse_ranges(input);
-    sum_invalid_ids_v2(&ranges)
+pub fn part2(ranges: &[(i64, i64)]) -> i128 {
+    sum_invalid(&RepetitionRule::at_least(2), ranges)
 }
 
-/// Entry point for running Day 2 solutions
-pub fn run() {
-    let input = fs::read_to_string("puzzles/day02/input.txt")
-        .expect("Failed to read input file");
+/// A total as a `u64` when it fits, which every real input does
+fn answer(total: i128) -> Answer {
+    match u64::try_from(total) {
+        Ok(total) => total.into(),
+        Err(_) => total.into(),
+    }
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Gift Shop";
+    type Input = Vec<(i64, i64)>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_ranges(input)
+    }
+
+    fn parse_lenient(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(parse_ranges_lenient(input))
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, String> {
+        Ok(answer(part1(input)))
+    }
 
-    println!("Day 2: Gift Shop");
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    fn part2(input: &Self::Input) -> Result<Answer, String> {
+        Ok(answer(part2(input)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Check if a number is invalid (made of a pattern repeated exactly twice)
+    /// Examples: 11 (1 repeated), 6464 (64 repeated), 123123 (123 repeated)
+    /// Not invalid: 101 (has leading zero when split), 111 (odd length)
+    fn is_invalid_id(n: i64) -> bool {
+        let s = n.to_string();
+        let len = s.len();
+
+        // Must have even length to be splittable into two equal parts
+        if !len.is_multiple_of(2) {
+            return false;
+        }
+
+        let half = len / 2;
+        let first_half = &s[..half];
+        let second_half = &s[half..];
+
+        // Check if both halves are equal
+        if first_half != second_half {
+            return false;
+        }
+
+        // Check for leading zeros - first half shouldn't start with 0
+        // (unless it's just "0", but then the number would be 00 which is 0, not a valid pattern)
+        if first_half.starts_with('0') {
+            return false;
+        }
+
+        true
+    }
+
+    /// Check if a number is invalid for Part 2 (made of a pattern repeated at least twice)
+    /// Examples: 11 (1x2), 111 (1x3), 6464 (64x2), 123123 (123x2), 123123123 (123x3)
+    fn is_invalid_id_v2(n: i64) -> bool {
+        let s = n.to_string();
+        let len = s.len();
+
+        // Need at least 2 digits to have a repeated pattern
+        if len < 2 {
+            return false;
+        }
+
+        // Try each possible pattern length from 1 to len/2
+        for pattern_len in 1..=len / 2 {
+            // Length must be divisible by pattern length
+            if !len.is_multiple_of(pattern_len) {
+                continue;
+            }
+
+            let repetitions = len / pattern_len;
+            if repetitions < 2 {
+                continue;
+            }
+
+            let pattern = &s[..pattern_len];
+
+            // Pattern cannot have leading zeros
+            if pattern.starts_with('0') {
+                continue;
+            }
+
+            // Check if the entire string is this pattern repeated
+            let mut is_repeated = true;
+            for i in 1..repetitions {
+                let start = i * pattern_len;
+                let end = start + pattern_len;
+                if &s[start..end] != pattern {
+                    is_repeated = false;
+                    break;
+                }
+            }
+
+            if is_repeated {
+                return true;
+            }
+        }
+
+        false
+    }
+
     const EXAMPLE_INPUT: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
 
     #[test]
@@ -192,91 +437,72 @@ mod tests {
     #[test]
     fn test_parse_ranges() {
         let input = "11-22,95-115";
-        let ranges = parse_ranges(input);
+        let ranges = parse_ranges(input).unwrap();
         assert_eq!(ranges.len(), 2);
         assert_eq!(ranges[0], (11, 22));
         assert_eq!(ranges[1], (95, 115));
     }
 
+    #[test]
+    fn test_parse_ranges_errors() {
+        assert_eq!(
+            parse_ranges("11-22,95x115"),
+            Err(ParseError::new(1, 7, "expected a range like '11-22', found '95x115'"))
+        );
+        assert_eq!(
+            parse_ranges("11-22,\n95-1a5"),
+            Err(ParseError::new(2, 4, "invalid number '1a5'"))
+        );
+        assert_eq!(
+            parse_ranges("22-11"),
+            Err(ParseError::new(1, 1, "range start is greater than its end"))
+        );
+    }
+
+    #[test]
+    fn test_parse_ranges_lenient() {
+        assert_eq!(parse_ranges_lenient("11-22,bad,95-115,"), vec![(11, 22), (95, 115)]);
+    }
+
     #[test]
     fn test_range_11_22() {
         // Should find 11 and 22
-        let ranges = vec![(11, 22)];
-        let mut invalids = vec![];
-        for &(start, end) in &ranges {
-            for id in start..=end {
-                if is_invalid_id(id) {
-                    invalids.push(id);
-                }
-            }
-        }
+        let invalids = RepetitionRule::exactly(2).list(11, 22);
         assert_eq!(invalids, vec![11, 22], "Range 11-22 should have invalid IDs: 11, 22");
     }
 
     #[test]
     fn test_range_95_115() {
         // Should find 99
-        let ranges = vec![(95, 115)];
-        let mut invalids = vec![];
-        for &(start, end) in &ranges {
-            for id in start..=end {
-                if is_invalid_id(id) {
-                    invalids.push(id);
-                }
-            }
-        }
+        let invalids = RepetitionRule::exactly(2).list(95, 115);
         assert_eq!(invalids, vec![99], "Range 95-115 should have invalid ID: 99");
     }
 
     #[test]
     fn test_range_998_1012() {
         // Should find 1010
-        let ranges = vec![(998, 1012)];
-        let mut invalids = vec![];
-        for &(start, end) in &ranges {
-            for id in start..=end {
-                if is_invalid_id(id) {
-                    invalids.push(id);
-                }
-            }
-        }
+        let invalids = RepetitionRule::exactly(2).list(998, 1012);
         assert_eq!(invalids, vec![1010], "Range 998-1012 should have invalid ID: 1010");
     }
 
     #[test]
     fn test_range_222220_222224() {
         // Should find 222222
-        let ranges = vec![(222220, 222224)];
-        let mut invalids = vec![];
-        for &(start, end) in &ranges {
-            for id in start..=end {
-                if is_invalid_id(id) {
-                    invalids.push(id);
-                }
-            }
-        }
+        let invalids = RepetitionRule::exactly(2).list(222220, 222224);
         assert_eq!(invalids, vec![222222], "Range 222220-222224 should have invalid ID: 222222");
     }
 
     #[test]
     fn test_range_446443_446449() {
         // Should find 446446
-        let ranges = vec![(446443, 446449)];
-        let mut invalids = vec![];
-        for &(start, end) in &ranges {
-            for id in start..=end {
-                if is_invalid_id(id) {
-                    invalids.push(id);
-                }
-            }
-        }
+        let invalids = RepetitionRule::exactly(2).list(446443, 446449);
         assert_eq!(invalids, vec![446446], "Range 446443-446449 should have invalid ID: 446446");
     }
 
     #[test]
     fn test_part1_example() {
         // Expected: 1227775554
-        let result = part1(EXAMPLE_INPUT);
+        let result = part1(&parse_ranges(EXAMPLE_INPUT).unwrap());
         assert_eq!(result, 1227775554, "Part 1 example should sum to 1227775554");
     }
 
@@ -329,31 +555,169 @@ mod tests {
     #[test]
     fn test_range_95_115_v2() {
         // Part 2: Should find 99 and 111
-        let mut invalids = vec![];
-        for id in 95..=115 {
-            if is_invalid_id_v2(id) {
-                invalids.push(id);
-            }
-        }
+        let invalids = RepetitionRule::at_least(2).list(95, 115);
         assert_eq!(invalids, vec![99, 111], "Range 95-115 should have invalid IDs: 99, 111");
     }
 
     #[test]
     fn test_range_998_1012_v2() {
         // Part 2: Should find 999 and 1010
-        let mut invalids = vec![];
-        for id in 998..=1012 {
-            if is_invalid_id_v2(id) {
-                invalids.push(id);
-            }
-        }
+        let invalids = RepetitionRule::at_least(2).list(998, 1012);
         assert_eq!(invalids, vec![999, 1010], "Range 998-1012 should have invalid IDs: 999, 1010");
     }
 
+    #[test]
+    fn test_closed_form_matches_brute_force() {
+        let ranges = [(1, 10_000), (95, 115), (998, 1012), (123_000, 125_000), (999_990, 1_010_101), (10, 10)];
+        for range in ranges {
+            let (start, end) = range;
+            let doubled: i128 = (start..=end).filter(|&id| is_invalid_id(id)).map(i128::from).sum();
+            let repeated: i128 = (start..=end).filter(|&id| is_invalid_id_v2(id)).map(i128::from).sum();
+            assert_eq!(part1(&[range]), doubled, "part 1 for {:?}", range);
+            assert_eq!(part2(&[range]), repeated, "part 2 for {:?}", range);
+        }
+    }
+
+    #[test]
+    fn test_repeat_multiplier() {
+        assert_eq!(12 * repeat_multiplier(10, 2, 3), 121212);
+        assert_eq!(7 * repeat_multiplier(10, 1, 6), 777777);
+        assert_eq!(123 * repeat_multiplier(10, 3, 2), 123123);
+        assert_eq!(0b101 * repeat_multiplier(2, 3, 2), 0b101101);
+    }
+
+    #[test]
+    fn test_counts_each_id_once() {
+        // 111111 is 1x6, 11x3 and 111x2 but is only one ID
+        assert_eq!(part2(&[(111111, 111111)]), 111111);
+        assert_eq!(part1(&[(111111, 111111)]), 111111);
+        assert_eq!(part1(&[(111, 111)]), 0);
+    }
+
+    #[test]
+    fn test_huge_range() {
+        // 11 to 99, then every 2-digit seed doubled
+        assert_eq!(part1(&[(1, 9999)]), (10..=99).map(|seed| seed * 101).sum::<i128>() + 495);
+
+        // Every doubled ID up to i64::MAX has at most 18 digits, so the
+        // total is each k-digit seed times 10^k + 1, for k up to 9
+        let all_doubled: i128 = (1..=9)
+            .map(|k| {
+                let (low, high) = (10i128.pow(k - 1), 10i128.pow(k) - 1);
+                (low + high) * (high - low + 1) / 2 * (10i128.pow(k) + 1)
+            })
+            .sum();
+        assert_eq!(all_doubled, 495_495_495_540_950_040_450_040_950);
+        assert_eq!(part1(&[(1, i64::MAX)]), all_doubled);
+
+        // Narrow windows of 18-digit IDs are small enough for the checkers
+        let windows = [
+            (123_123_123_123_122_000, 123_123_123_123_124_000),
+            (565_656_565_656_565_000, 565_656_565_656_566_000),
+            (999_999_999_999_998_000, 1_000_000_000_000_001_000),
+        ];
+        for (start, end) in windows {
+            let doubled: i128 = (start..=end).filter(|&id| is_invalid_id(id)).map(i128::from).sum();
+            let repeated: i128 = (start..=end).filter(|&id| is_invalid_id_v2(id)).map(i128::from).sum();
+            assert!(repeated > 0, "window {}-{} has a repeat", start, end);
+            assert_eq!(part1(&[(start, end)]), doubled, "part 1 for {}-{}", start, end);
+            assert_eq!(part2(&[(start, end)]), repeated, "part 2 for {}-{}", start, end);
+        }
+    }
+
+    #[test]
+    fn test_presets_match_checkers() {
+        let (doubled, repeated) = (RepetitionRule::exactly(2), RepetitionRule::at_least(2));
+        for id in 1..20_000 {
+            assert_eq!(doubled.matches(id), is_invalid_id(id), "part 1 rule for {}", id);
+            assert_eq!(repeated.matches(id), is_invalid_id_v2(id), "part 2 rule for {}", id);
+        }
+        assert_eq!(repeated, RepetitionRule::default());
+    }
+
+    #[test]
+    fn test_rule_queries() {
+        let rule = RepetitionRule::at_least(2);
+        assert_eq!(rule.list(95, 1012), [99, 111, 222, 333, 444, 555, 666, 777, 888, 999, 1010]);
+        assert_eq!(rule.count(95, 1012), 11);
+        assert_eq!(rule.sum(95, 1012), rule.list(95, 1012).iter().map(|&id| i128::from(id)).sum::<i128>());
+        assert_eq!(rule.count(20, 10), 0, "empty range");
+    }
+
+    #[test]
+    fn test_prime_repetitions_only() {
+        // Odd primes only: 111111 is 1x6 but also 11x3, so it counts
+        let primes = RepetitionRule::new(10, 2, None, Some(vec![3, 5, 7])).unwrap();
+        assert!(primes.matches(111111));
+        assert!(!primes.matches(12121212), "12x4 and 1212x2, neither allowed");
+        assert!(primes.matches(1212121212), "12x5");
+        assert!(!primes.matches(1111));
+        // 4 repeats only: 1111 is 1x4 but 11 is not allowed to count
+        let four = RepetitionRule::exactly(4);
+        assert_eq!(four.list(1, 100_000_000), [1111, 2222, 3333, 4444, 5555, 6666, 7777, 8888, 9999]
+            .into_iter()
+            .chain((10..100).map(|seed| seed * 1010101))
+            .collect::<Vec<i64>>());
+        let list = primes.list(1, 1_000_000);
+        assert_eq!(list, (1..1_000_000).filter(|&id| primes.matches(id)).collect::<Vec<i64>>());
+        assert_eq!(primes.count(1, 1_000_000), list.len() as u64);
+        assert_eq!(primes.sum(1, 1_000_000), list.iter().map(|&id| i128::from(id)).sum::<i128>());
+    }
+
+    #[test]
+    fn test_other_bases() {
+        // In binary, 0b1010 = 10 is 10 repeated and 0b111 = 7 is 1 three times
+        let binary = RepetitionRule::new(2, 2, None, None).unwrap();
+        assert_eq!(binary.list(1, 15), [3, 7, 10, 15]);
+        assert_eq!(binary.sum(1, 15), 35);
+        let hex_doubled = RepetitionRule::new(16, 2, Some(2), None).unwrap();
+        assert_eq!(hex_doubled.count(0, 0xffff), 15 + 240);
+        assert!(hex_doubled.matches(0xabab));
+    }
+
     #[test]
     fn test_part2_example() {
         // Expected: 4174379265
-        let result = part2(EXAMPLE_INPUT);
+        let result = part2(&parse_ranges(EXAMPLE_INPUT).unwrap());
         assert_eq!(result, 4174379265, "Part 2 example should sum to 4174379265");
     }
+
+    #[test]
+    fn test_answer_variants() {
+        let example = parse_ranges(EXAMPLE_INPUT).unwrap();
+        assert_eq!(Day02::part1(&example), Ok(Answer::Unsigned(1227775554)));
+        assert_eq!(Day02::part2(&example), Ok(Answer::Unsigned(4174379265)));
+        assert_eq!(
+            Day02::part1(&vec![(1, i64::MAX)]),
+            Ok(Answer::Big(495_495_495_540_950_040_450_040_950))
+        );
+    }
+
+    #[test]
+    fn test_rule_rejects_small_bases() {
+        assert_eq!(RepetitionRule::new(1, 2, None, None), Err("base must be at least 2, found 1".to_string()));
+        assert_eq!(RepetitionRule::new(0, 2, None, None), Err("base must be at least 2, found 0".to_string()));
+        assert_eq!(RepetitionRule::new(10, 2, None, None), Ok(RepetitionRule::at_least(2)));
+    }
+
+    #[test]
+    fn test_rule_rejects_single_blocks() {
+        assert_eq!(
+            RepetitionRule::new(10, 1, None, None),
+            Err("a block must repeat at least twice, found 1".to_string())
+        );
+        assert_eq!(
+            RepetitionRule::new(10, 0, Some(3), None),
+            Err("a block must repeat at least twice, found 0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rule_rejects_empty_range_of_counts() {
+        assert_eq!(
+            RepetitionRule::new(10, 3, Some(2), None),
+            Err("at most 2 repeats is fewer than the minimum of 3".to_string())
+        );
+        assert_eq!(RepetitionRule::new(10, 3, Some(3), None), Ok(RepetitionRule::exactly(3)));
+    }
 }
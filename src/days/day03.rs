@@ -1,100 +1,179 @@
-/// Day 3: Lobby
-///
-/// Find the maximum joltage possible from each battery bank by turning on exactly two batteries.
-/// The joltage is the number formed by the two selected digits.
-/// Sum the maximum joltages from all banks.
+//! Day 3: Lobby
+//!
+//! Find the maximum joltage possible from each battery bank by turning on exactly two batteries.
+//! The joltage is the number formed by the two selected digits.
+//! Sum the maximum joltages from all banks.
+//!
+//! Both parts share one selector that turns on any number of batteries in
+//! linear time, and totals are summed as decimal digits so they never
+//! overflow.
 
-use std::fs;
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::utils::parse_lines;
 
 /// Parse input into lines representing battery banks
-fn parse_input(input: &str) -> Vec<&str> {
-    input.lines().filter(|line| !line.is_empty()).collect()
-}
-
-/// Find the maximum joltage for a single battery bank
-/// by selecting exactly two digits to form the largest possible number
-fn max_joltage_for_bank(bank: &str) -> i64 {
-    let digits: Vec<char> = bank.chars().collect();
-    let mut max = 0i64;
-
-    // Try all pairs of positions
-    for i in 0..digits.len() {
-        for j in (i + 1)..digits.len() {
-            // Form a two-digit number from positions i and j
-            let first = digits[i].to_digit(10).unwrap() as i64;
-            let second = digits[j].to_digit(10).unwrap() as i64;
-            let value = first * 10 + second;
-            max = max.max(value);
+fn parse_input(input: &str) -> Result<Vec<String>, ParseError> {
+    parse_lines(input, |line_no, line| {
+        let bank = line.trim();
+        if let Some((idx, ch)) = bank.char_indices().find(|(_, ch)| !ch.is_ascii_digit()) {
+            return Err(ParseError::at(
+                line_no,
+                line,
+                &bank[idx..],
+                format!("expected a battery digit, found '{}'", ch),
+            ));
         }
-    }
-
-    max
+        Ok(bank.to_string())
+    })
 }
 
-/// Part 1 solution
-pub fn part1(input: &str) -> i64 {
-    let banks = parse_input(input);
-    banks.iter().map(|bank| max_joltage_for_bank(bank)).sum()
+/// The batteries turned on in a bank
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    /// Positions of the chosen batteries, in increasing order
+    pub indices: Vec<usize>,
+    digits: String,
 }
 
-/// Find the maximum joltage for a bank by selecting exactly n batteries
-/// Strategy: Use a greedy approach to select the n largest digits while maintaining order
-fn max_joltage_for_bank_n(bank: &str, n: usize) -> i64 {
-    let digits: Vec<char> = bank.chars().collect();
-    let len = digits.len();
-
-    if n >= len {
-        // If we need all or more digits than available, use all
-        return bank.parse::<i64>().unwrap_or(0);
+impl Selection {
+    /// The joltage as decimal digits, leading zeros included
+    pub fn as_str(&self) -> &str {
+        &self.digits
     }
 
-    // Greedy approach: for each position in the result, pick the largest digit
-    // that still leaves enough digits for the remaining positions
-    let mut result = String::new();
-    let mut start_idx = 0;
-
-    for position in 0..n {
-        let remaining_after_this = n - position - 1;
-        let search_end = len - remaining_after_this;
-
-        // Find the largest digit in the valid range
-        let mut max_digit = '0';
-        let mut max_idx = start_idx;
+    /// The joltage as a number, or `None` if it needs more than 128 bits
+    ///
+    /// There is no wider integer type, so larger joltages are only available
+    /// as digits, from [`Selection::as_str`].
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.digits.is_empty() {
+            return Some(0);
+        }
+        self.digits.parse().ok()
+    }
+}
 
-        for i in start_idx..search_end {
-            if digits[i] > max_digit {
-                max_digit = digits[i];
-                max_idx = i;
-            }
+/// Turn on `k` batteries in `bank` to make the largest joltage
+///
+/// Keeps a stack of chosen digits and pops any smaller than the next one
+/// while there are still digits to spare, so each digit is pushed and
+/// popped at most once. If `k` is at least the bank's length, every battery
+/// is turned on.
+pub fn select_batteries(bank: &str, k: usize) -> Selection {
+    let digits = bank.as_bytes();
+    let mut spare = digits.len().saturating_sub(k);
+    let mut stack: Vec<usize> = Vec::with_capacity(digits.len());
+    for (idx, &digit) in digits.iter().enumerate() {
+        while spare > 0 && stack.last().is_some_and(|&top| digits[top] < digit) {
+            stack.pop();
+            spare -= 1;
         }
+        stack.push(idx);
+    }
+    // Digits left to drop come off the end, where they matter least
+    stack.truncate(k);
+    let digits = stack.iter().map(|&idx| char::from(digits[idx])).collect();
+    Selection {
+        indices: stack,
+        digits,
+    }
+}
 
-        result.push(max_digit);
-        start_idx = max_idx + 1;
+/// Sum of two decimal digit strings, without leading zeros
+fn add_decimal(a: &str, b: &str) -> String {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let digit = |s: &[u8], place: usize| if place < s.len() { s[s.len() - 1 - place] - b'0' } else { 0 };
+    let mut sum = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+    for place in 0..a.len().max(b.len()) {
+        let total = digit(a, place) + digit(b, place) + carry;
+        sum.push(char::from(b'0' + total % 10));
+        carry = total / 10;
+    }
+    if carry > 0 {
+        sum.push(char::from(b'0' + carry));
+    }
+    while sum.len() > 1 && sum.last() == Some(&'0') {
+        sum.pop();
     }
+    if sum.is_empty() {
+        return "0".to_string();
+    }
+    sum.iter().rev().collect()
+}
+
+/// Sum of every bank's largest `k`-battery joltage, exact for any `k`
+///
+/// The total is returned as decimal digits because it can be wider than
+/// 128 bits once `k` passes 38.
+pub fn total_joltage(banks: &[String], k: usize) -> String {
+    banks.iter().fold("0".to_string(), |total, bank| {
+        add_decimal(&total, select_batteries(bank, k).as_str())
+    })
+}
 
-    result.parse::<i64>().unwrap_or(0)
+/// Part 1 solution
+pub fn part1(banks: &[String]) -> String {
+    total_joltage(banks, 2)
 }
 
 /// Part 2 solution
-pub fn part2(input: &str) -> i64 {
-    let banks = parse_input(input);
-    banks.iter().map(|bank| max_joltage_for_bank_n(bank, 12)).sum()
+pub fn part2(banks: &[String]) -> String {
+    total_joltage(banks, 12)
 }
 
-/// Entry point for running this day
-pub fn run() {
-    let input = fs::read_to_string("puzzles/day03/input.txt")
-        .expect("Failed to read input file");
+/// A total as the narrowest number it fits in, otherwise as its digits
+fn answer(total: String) -> Answer {
+    if let Ok(n) = total.parse::<u64>() {
+        n.into()
+    } else if let Ok(n) = total.parse::<i128>() {
+        n.into()
+    } else {
+        total.into()
+    }
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Lobby";
+    type Input = Vec<String>;
 
-    println!("Day 3: Lobby");
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, String> {
+        Ok(answer(part1(input)))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, String> {
+        Ok(answer(part2(input)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// The largest joltage over every choice of `k` batteries
+    fn brute_force(bank: &str, k: usize) -> String {
+        let digits = bank.as_bytes();
+        (0u32..1 << digits.len())
+            .filter(|mask| mask.count_ones() as usize == k)
+            .map(|mask| {
+                (0..digits.len())
+                    .filter(|&idx| mask & (1 << idx) != 0)
+                    .map(|idx| char::from(digits[idx]))
+                    .collect::<String>()
+            })
+            .max()
+            .unwrap()
+    }
+
     const EXAMPLE_INPUT: &str = "987654321111111
 811111111111119
 234234234234278
@@ -102,63 +181,121 @@ mod tests {
 
     #[test]
     fn test_part1_example() {
-        let result = part1(EXAMPLE_INPUT);
-        assert_eq!(result, 357, "Example should return 357 (98+89+78+92)");
+        let result = part1(&parse_input(EXAMPLE_INPUT).unwrap());
+        assert_eq!(result, "357", "Example should return 357 (98+89+78+92)");
+    }
+
+    #[test]
+    fn test_parse_input_rejects_non_digits() {
+        assert_eq!(
+            parse_input("12345\n98x76"),
+            Err(ParseError::new(2, 3, "expected a battery digit, found 'x'"))
+        );
     }
 
     #[test]
     fn test_max_joltage_bank1() {
-        assert_eq!(max_joltage_for_bank("987654321111111"), 98);
+        assert_eq!(select_batteries("987654321111111", 2).as_str(), "98");
     }
 
     #[test]
     fn test_max_joltage_bank2() {
-        assert_eq!(max_joltage_for_bank("811111111111119"), 89);
+        assert_eq!(select_batteries("811111111111119", 2).as_str(), "89");
     }
 
     #[test]
     fn test_max_joltage_bank3() {
-        assert_eq!(max_joltage_for_bank("234234234234278"), 78);
+        assert_eq!(select_batteries("234234234234278", 2).as_str(), "78");
     }
 
     #[test]
     fn test_max_joltage_bank4() {
-        assert_eq!(max_joltage_for_bank("818181911112111"), 92);
+        assert_eq!(select_batteries("818181911112111", 2).as_str(), "92");
     }
 
     #[test]
     fn test_simple_bank() {
-        assert_eq!(max_joltage_for_bank("12345"), 45);
+        assert_eq!(select_batteries("12345", 2).as_str(), "45");
     }
 
     #[test]
     fn test_two_digit_bank() {
-        assert_eq!(max_joltage_for_bank("24"), 24);
+        assert_eq!(select_batteries("24", 2).as_str(), "24");
     }
 
     #[test]
     fn test_part2_example() {
-        let result = part2(EXAMPLE_INPUT);
-        assert_eq!(result, 3121910778619, "Part 2 example should return 3121910778619");
+        let result = part2(&parse_input(EXAMPLE_INPUT).unwrap());
+        assert_eq!(result, "3121910778619", "Part 2 example should return 3121910778619");
     }
 
     #[test]
     fn test_max_joltage_12_bank1() {
-        assert_eq!(max_joltage_for_bank_n("987654321111111", 12), 987654321111);
+        assert_eq!(select_batteries("987654321111111", 12).as_str(), "987654321111");
     }
 
     #[test]
     fn test_max_joltage_12_bank2() {
-        assert_eq!(max_joltage_for_bank_n("811111111111119", 12), 811111111119);
+        assert_eq!(select_batteries("811111111111119", 12).as_str(), "811111111119");
     }
 
     #[test]
     fn test_max_joltage_12_bank3() {
-        assert_eq!(max_joltage_for_bank_n("234234234234278", 12), 434234234278);
+        assert_eq!(select_batteries("234234234234278", 12).as_str(), "434234234278");
     }
 
     #[test]
     fn test_max_joltage_12_bank4() {
-        assert_eq!(max_joltage_for_bank_n("818181911112111", 12), 888911112111);
+        assert_eq!(select_batteries("818181911112111", 12).as_str(), "888911112111");
+    }
+
+    #[test]
+    fn test_selected_indices() {
+        let selection = select_batteries("818181911112111", 4);
+        assert_eq!(selection.indices, [6, 11, 12, 13]);
+        assert_eq!(selection.as_str(), "9211");
+        assert_eq!(select_batteries("31", 2).indices, [0, 1]);
+        assert_eq!(select_batteries("555", 2).indices, [0, 1], "ties keep the earliest");
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let banks = ["4193857", "1111", "90909", "5127312", "8", "1029384756", "7771777"];
+        for bank in banks {
+            for k in 1..=bank.len() {
+                assert_eq!(select_batteries(bank, k).as_str(), brute_force(bank, k), "{} choosing {}", bank, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_more_than_eighteen_digits() {
+        let bank = "1".repeat(10) + &"9".repeat(20) + "123456789";
+        let selection = select_batteries(&bank, 25);
+        assert_eq!(selection.as_str(), "9".repeat(20) + "56789");
+        assert_eq!(selection.to_u128(), Some(10u128.pow(25) - 1 - 43210));
+        assert_eq!(select_batteries(&"9".repeat(50), 40).to_u128(), None);
+
+        let banks = vec!["9".repeat(50), "9".repeat(50)];
+        assert_eq!(total_joltage(&banks, 40), "1".to_string() + &"9".repeat(39) + "8");
+    }
+
+    #[test]
+    fn test_short_banks_and_zero() {
+        assert_eq!(select_batteries("42", 5).as_str(), "42", "too short turns everything on");
+        assert_eq!(select_batteries("42", 0).to_u128(), Some(0));
+        assert_eq!(total_joltage(&["007".to_string(), "0".to_string()], 2), "7");
+        assert_eq!(total_joltage(&[], 12), "0");
+        assert_eq!(add_decimal("999", "1"), "1000");
+    }
+
+    #[test]
+    fn test_answer_variants() {
+        let banks = parse_input(EXAMPLE_INPUT).unwrap();
+        assert_eq!(Day03::part1(&banks), Ok(Answer::Unsigned(357)));
+        assert_eq!(Day03::part2(&banks), Ok(Answer::Unsigned(3121910778619)));
+        assert_eq!(answer("1".to_string() + &"0".repeat(20)), Answer::Big(10i128.pow(20)));
+        let wide = "9".repeat(40);
+        assert_eq!(answer(wide.clone()), Answer::Text(wide));
     }
 }
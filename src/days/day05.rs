@@ -1,92 +1,105 @@
-/// Day 5: Cafeteria
-///
-/// Determine which available ingredient IDs are fresh by checking if they
-/// fall within any of the fresh ingredient ID ranges.
-
-use std::fs;
-
-/// Parse a range line like "3-5" into (start, end)
-fn parse_range(line: &str) -> (i64, i64) {
-    let parts: Vec<&str> = line.trim().split('-').collect();
-    let start: i64 = parts[0].parse().unwrap();
-    let end: i64 = parts[1].parse().unwrap();
-    (start, end)
+//! Day 5: Cafeteria
+//!
+//! Determine which available ingredient IDs are fresh by checking if they
+//! fall within any of the fresh ingredient ID ranges.
+
+use crate::answer::Answer;
+use crate::error::{parse_number, ParseError};
+use crate::solution::Solution;
+use crate::utils::IntervalSet;
+
+/// Fresh ID ranges, merged, and the available ingredient IDs
+type Inventory = (IntervalSet, Vec<i64>);
+
+/// Parse a range line like "3-5" into (start, end), rejecting reversed ranges
+fn parse_range(line_no: usize, line: &str) -> Result<(i64, i64), ParseError> {
+    let trimmed = line.trim();
+    let (start, end) = trimmed.split_once('-').ok_or_else(|| {
+        ParseError::at(line_no, line, trimmed, format!("expected a range like '3-5', found '{}'", trimmed))
+    })?;
+    let (start, end) = (parse_number(line_no, line, start)?, parse_number(line_no, line, end)?);
+    if start > end {
+        return Err(ParseError::at(
+            line_no,
+            line,
+            trimmed,
+            format!("range '{}' ends before it starts", trimmed),
+        ));
+    }
+    Ok((start, end))
 }
 
 /// Parse the input into (ranges, ingredient_ids)
-fn parse_input(input: &str) -> (Vec<(i64, i64)>, Vec<i64>) {
-    let parts: Vec<&str> = input.split("\n\n").collect();
-
-    let ranges: Vec<(i64, i64)> = parts[0]
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| parse_range(line))
-        .collect();
-
-    let ingredient_ids: Vec<i64> = parts[1]
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| line.trim().parse().unwrap())
-        .collect();
-
-    (ranges, ingredient_ids)
+///
+/// The two sections are separated by the first blank line.
+fn parse_input(input: &str) -> Result<Inventory, ParseError> {
+    let mut ranges = Vec::new();
+    let mut ingredient_ids = Vec::new();
+    let mut in_ranges = true;
+    let mut last_line = 0;
+
+    for (idx, line) in input.lines().enumerate() {
+        let line_no = idx + 1;
+        last_line = line_no;
+        if line.trim().is_empty() {
+            in_ranges = false;
+        } else if in_ranges {
+            ranges.push(parse_range(line_no, line)?);
+        } else {
+            ingredient_ids.push(parse_number(line_no, line, line)?);
+        }
+    }
+
+    if in_ranges {
+        return Err(ParseError::new(
+            last_line + 1,
+            1,
+            "missing blank line between ranges and ingredient IDs",
+        ));
+    }
+
+    Ok((ranges.into_iter().collect(), ingredient_ids))
 }
 
 /// Check if an ingredient ID is fresh (falls within any range)
-fn is_fresh(id: i64, ranges: &[(i64, i64)]) -> bool {
-    ranges.iter().any(|(start, end)| id >= *start && id <= *end)
+fn is_fresh(id: i64, fresh: &IntervalSet) -> bool {
+    fresh.contains(id)
 }
 
 /// Part 1: Count how many available ingredient IDs are fresh
-pub fn part1(input: &str) -> i64 {
-    let (ranges, ingredient_ids) = parse_input(input);
-    ingredient_ids.iter().filter(|&id| is_fresh(*id, &ranges)).count() as i64
+pub fn part1(fresh: &IntervalSet, ingredient_ids: &[i64]) -> i64 {
+    ingredient_ids.iter().filter(|&&id| is_fresh(id, fresh)).count() as i64
 }
 
-/// Merge overlapping ranges and return total count of unique IDs
-fn merge_ranges(ranges: &[(i64, i64)]) -> i64 {
-    if ranges.is_empty() {
-        return 0;
-    }
+/// Part 2: Count total unique fresh ingredient IDs from all ranges
+///
+/// Up to 2^64 of them, one more than fits in a `u64`.
+pub fn part2(fresh: &IntervalSet) -> u128 {
+    fresh.total_len()
+}
 
-    // Sort ranges by start
-    let mut sorted: Vec<(i64, i64)> = ranges.to_vec();
-    sorted.sort_by_key(|r| r.0);
+pub struct Day05;
 
-    // Merge overlapping ranges
-    let mut merged: Vec<(i64, i64)> = Vec::new();
-    let mut current = sorted[0];
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "Cafeteria";
+    type Input = Inventory;
 
-    for &(start, end) in &sorted[1..] {
-        if start <= current.1 + 1 {
-            // Overlapping or adjacent, extend current range
-            current.1 = current.1.max(end);
-        } else {
-            // No overlap, save current and start new
-            merged.push(current);
-            current = (start, end);
-        }
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
     }
-    merged.push(current);
 
-    // Count total IDs in merged ranges
-    merged.iter().map(|(start, end)| end - start + 1).sum()
-}
-
-/// Part 2: Count total unique fresh ingredient IDs from all ranges
-pub fn part2(input: &str) -> i64 {
-    let (ranges, _) = parse_input(input);
-    merge_ranges(&ranges)
-}
-
-/// Entry point for running Day 5 solutions
-pub fn run() {
-    let input = fs::read_to_string("puzzles/day05/input.txt")
-        .expect("Failed to read input file");
+    fn part1(input: &Self::Input) -> Result<Answer, String> {
+        Ok(part1(&input.0, &input.1).into())
+    }
 
-    println!("Day 5: Cafeteria");
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    fn part2(input: &Self::Input) -> Result<Answer, String> {
+        let total = part2(&input.0);
+        Ok(match u64::try_from(total) {
+            Ok(total) => total.into(),
+            Err(_) => (total as i128).into(),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -108,74 +121,90 @@ mod tests {
 
     #[test]
     fn test_parse_range() {
-        assert_eq!(parse_range("3-5"), (3, 5));
-        assert_eq!(parse_range("10-14"), (10, 14));
-        assert_eq!(parse_range("16-20"), (16, 20));
+        assert_eq!(parse_range(1, "3-5"), Ok((3, 5)));
+        assert_eq!(parse_range(1, "10-14"), Ok((10, 14)));
+        assert_eq!(parse_range(1, "16-20"), Ok((16, 20)));
+    }
+
+    #[test]
+    fn test_parse_input_errors() {
+        assert_eq!(
+            parse_input("3-5\n10:14\n\n1\n"),
+            Err(ParseError::new(2, 1, "expected a range like '3-5', found '10:14'"))
+        );
+        assert_eq!(
+            parse_input("3-5\n\n1\nseven\n"),
+            Err(ParseError::new(4, 1, "invalid number 'seven'"))
+        );
+        assert_eq!(
+            parse_input("3-5\n10-14"),
+            Err(ParseError::new(3, 1, "missing blank line between ranges and ingredient IDs"))
+        );
+        assert_eq!(
+            parse_input("3-5
+ 14-10
+
+1
+"),
+            Err(ParseError::new(2, 2, "range '14-10' ends before it starts"))
+        );
     }
 
     #[test]
     fn test_parse_input() {
-        let (ranges, ids) = parse_input(EXAMPLE_INPUT);
-        assert_eq!(ranges.len(), 4);
-        assert_eq!(ranges[0], (3, 5));
-        assert_eq!(ranges[1], (10, 14));
-        assert_eq!(ranges[2], (16, 20));
-        assert_eq!(ranges[3], (12, 18));
+        let (fresh, ids) = parse_input(EXAMPLE_INPUT).unwrap();
+        // 10-14, 16-20 and 12-18 merge into one span
+        assert_eq!(fresh.iter().collect::<Vec<_>>(), vec![(3, 5), (10, 20)]);
         assert_eq!(ids, vec![1, 5, 8, 11, 17, 32]);
     }
 
     #[test]
     fn test_is_fresh() {
-        let ranges = vec![(3, 5), (10, 14), (16, 20), (12, 18)];
+        let (fresh, _) = parse_input(EXAMPLE_INPUT).unwrap();
 
         // ID 1 is spoiled (not in any range)
-        assert!(!is_fresh(1, &ranges));
+        assert!(!is_fresh(1, &fresh));
 
         // ID 5 is fresh (in range 3-5)
-        assert!(is_fresh(5, &ranges));
+        assert!(is_fresh(5, &fresh));
 
         // ID 8 is spoiled
-        assert!(!is_fresh(8, &ranges));
+        assert!(!is_fresh(8, &fresh));
 
         // ID 11 is fresh (in range 10-14)
-        assert!(is_fresh(11, &ranges));
+        assert!(is_fresh(11, &fresh));
 
         // ID 17 is fresh (in range 16-20 and 12-18)
-        assert!(is_fresh(17, &ranges));
+        assert!(is_fresh(17, &fresh));
 
         // ID 32 is spoiled
-        assert!(!is_fresh(32, &ranges));
+        assert!(!is_fresh(32, &fresh));
     }
 
     #[test]
     fn test_part1_example() {
-        assert_eq!(part1(EXAMPLE_INPUT), 3);
-    }
-
-    #[test]
-    fn test_merge_ranges() {
-        // Ranges: 3-5, 10-14, 16-20, 12-18
-        // After merge: 3-5, 10-20
-        // Count: 3 + 11 = 14
-        let ranges = vec![(3, 5), (10, 14), (16, 20), (12, 18)];
-        assert_eq!(merge_ranges(&ranges), 14);
+        let (fresh, ids) = parse_input(EXAMPLE_INPUT).unwrap();
+        assert_eq!(part1(&fresh, &ids), 3);
     }
 
     #[test]
-    fn test_merge_adjacent_ranges() {
+    fn test_part2_adjacent_ranges() {
         // Ranges: 1-3, 4-6 -> merged: 1-6 = 6 IDs
-        let ranges = vec![(1, 3), (4, 6)];
-        assert_eq!(merge_ranges(&ranges), 6);
+        let (fresh, _) = parse_input("1-3\n4-6\n\n").unwrap();
+        assert_eq!(part2(&fresh), 6);
     }
 
     #[test]
-    fn test_merge_single_range() {
-        let ranges = vec![(5, 10)];
-        assert_eq!(merge_ranges(&ranges), 6);
+    fn test_part2_example() {
+        let (fresh, _) = parse_input(EXAMPLE_INPUT).unwrap();
+        assert_eq!(part2(&fresh), 14);
+        assert_eq!(Day05::part2(&(fresh, vec![])), Ok(Answer::Unsigned(14)));
     }
 
     #[test]
-    fn test_part2_example() {
-        assert_eq!(part2(EXAMPLE_INPUT), 14);
+    fn test_part2_every_id() {
+        let fresh: IntervalSet = [(i64::MIN, -1), (0, i64::MAX)].into_iter().collect();
+        assert_eq!(part2(&fresh), 1 << 64);
+        assert_eq!(Day05::part2(&(fresh, vec![])), Ok(Answer::Big(1 << 64)));
     }
 }
@@ -1,13 +1,15 @@
-/// Day 6: Trash Compactor
-///
-/// Parse a horizontal math worksheet where numbers are arranged vertically
-/// in columns with operators at the bottom. Solve each problem and sum all answers.
+//! Day 6: Trash Compactor
+//!
+//! Parse a horizontal math worksheet where numbers are arranged vertically
+//! in columns with operators at the bottom. Solve each problem and sum all answers.
 
-use std::fs;
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::solution::Solution;
 
 /// Parse the input into a list of problems
 /// Each problem is a vector of numbers and an operator
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 enum Operator {
     Add,
     Multiply,
@@ -19,188 +21,182 @@ struct Problem {
     operator: Operator,
 }
 
-fn parse_input(input: &str) -> Vec<Problem> {
+/// Check the worksheet layout: digit rows followed by a single operator row
+fn validate_worksheet(input: &str) -> Result<(), ParseError> {
     let lines: Vec<&str> = input.lines().collect();
-    if lines.is_empty() {
-        return vec![];
-    }
-
-    // The last line contains operators
-    let operator_line = lines.last().unwrap();
-    let number_lines = &lines[..lines.len() - 1];
-
-    // Step 1: Find where each operator is located (these mark problem columns)
-    let mut operator_positions = Vec::new();
-    for (idx, ch) in operator_line.chars().enumerate() {
-        if ch == '*' || ch == '+' {
-            operator_positions.push((idx, ch));
+    let Some((operator_line, number_lines)) = lines.split_last() else {
+        return Err(ParseError::new(1, 1, "worksheet is empty"));
+    };
+
+    for (idx, line) in number_lines.iter().enumerate() {
+        if let Some((col, ch)) = line
+            .chars()
+            .enumerate()
+            .find(|(_, ch)| !ch.is_ascii_digit() && *ch != ' ')
+        {
+            return Err(ParseError::new(idx + 1, col + 1, format!("expected a digit, found '{}'", ch)));
         }
     }
 
-    // Step 2: For each operator position, extract all numbers from that problem
-    let mut problems = Vec::new();
-
-    for (op_col_idx, op_char) in operator_positions {
-        let operator = if op_char == '*' {
-            Operator::Multiply
-        } else {
-            Operator::Add
-        };
-
-        // For this problem, we need to find the column range it spans
-        // We'll look backwards and forwards from the operator to find the problem boundaries
-
-        // Find the start of this problem (leftmost column with content)
-        let mut start_col = op_col_idx;
-        for col in (0..op_col_idx).rev() {
-            let has_content = number_lines.iter().any(|line| {
-                col < line.len() && !line.chars().nth(col).unwrap().is_whitespace()
-            });
-            if has_content {
-                start_col = col;
-            } else {
-                break;
-            }
-        }
+    let operator_line_no = lines.len();
+    if let Some((col, ch)) = operator_line
+        .chars()
+        .enumerate()
+        .find(|(_, ch)| !matches!(ch, '*' | '+' | ' '))
+    {
+        return Err(ParseError::new(
+            operator_line_no,
+            col + 1,
+            format!("expected '*' or '+', found '{}'", ch),
+        ));
+    }
+    if !operator_line.contains(['*', '+']) {
+        return Err(ParseError::new(operator_line_no, 1, "missing operator row"));
+    }
 
-        // Find the end of this problem (rightmost column with content)
-        let mut end_col = op_col_idx;
-        let max_len = number_lines.iter().map(|l| l.len()).max().unwrap_or(0);
-        for col in (op_col_idx + 1)..max_len {
-            let has_content = number_lines.iter().any(|line| {
-                col < line.len() && !line.chars().nth(col).unwrap().is_whitespace()
-            });
-            if has_content {
-                end_col = col;
-            } else {
-                break;
-            }
-        }
+    Ok(())
+}
 
-        // Extract numbers from each row within this column range
-        let mut numbers = Vec::new();
-        for line in number_lines {
-            if start_col < line.len() {
-                let segment = &line[start_col..=end_col.min(line.len() - 1)];
-                if let Ok(num) = segment.trim().parse::<i64>() {
-                    numbers.push(num);
-                }
-            }
-        }
+/// One problem's slice of the worksheet: its operator and the characters of
+/// each number row within its columns, padded to the same width
+#[derive(Debug)]
+struct Block {
+    operator: Operator,
+    rows: Vec<Vec<u8>>,
+}
 
-        problems.push(Problem { numbers, operator });
+impl Block {
+    /// Numbers written left to right, one per row (part 1)
+    fn row_numbers(&self) -> Vec<i64> {
+        self.rows
+            .iter()
+            .filter_map(|row| std::str::from_utf8(row).ok()?.trim().parse().ok())
+            .collect()
     }
 
-    problems
+    /// Numbers written top to bottom, one per column, rightmost first (part 2)
+    fn column_numbers(&self) -> Vec<i64> {
+        let width = self.rows.first().map_or(0, Vec::len);
+        (0..width)
+            .rev()
+            .filter_map(|col| {
+                let digits: String = self
+                    .rows
+                    .iter()
+                    .map(|row| row[col] as char)
+                    .filter(char::is_ascii_digit)
+                    .collect();
+                digits.parse().ok()
+            })
+            .collect()
+    }
 }
 
-fn solve_problem(problem: &Problem) -> i64 {
-    match problem.operator {
-        Operator::Add => problem.numbers.iter().sum(),
-        Operator::Multiply => problem.numbers.iter().product(),
-    }
+/// The worksheet split into its problems
+///
+/// The parts read each problem's digits in different directions, so the model
+/// keeps every problem's block of characters rather than the numbers.
+#[derive(Debug)]
+pub struct Worksheet {
+    blocks: Vec<Block>,
 }
 
-/// Part 1 solution
-pub fn part1(input: &str) -> i64 {
-    let problems = parse_input(input);
-    problems.iter().map(|p| solve_problem(p)).sum()
+impl Worksheet {
+    fn problems(&self, read: impl Fn(&Block) -> Vec<i64>) -> Vec<Problem> {
+        self.blocks
+            .iter()
+            .map(|block| Problem {
+                numbers: read(block),
+                operator: block.operator,
+            })
+            .collect()
+    }
 }
 
-fn parse_input_part2(input: &str) -> Vec<Problem> {
+fn parse_input(input: &str) -> Result<Worksheet, ParseError> {
+    validate_worksheet(input)?;
     let lines: Vec<&str> = input.lines().collect();
-    if lines.is_empty() {
-        return vec![];
-    }
 
     // The last line contains operators
     let operator_line = lines.last().unwrap();
     let number_lines = &lines[..lines.len() - 1];
-
-    // Step 1: Find where each operator is located (these mark problem columns)
-    let mut operator_positions = Vec::new();
-    for (idx, ch) in operator_line.chars().enumerate() {
-        if ch == '*' || ch == '+' {
-            operator_positions.push((idx, ch));
-        }
-    }
-
-    // Step 2: For each operator position, extract numbers reading column-by-column from right to left
-    let mut problems = Vec::new();
-
-    for (op_col_idx, op_char) in operator_positions {
-        let operator = if op_char == '*' {
-            Operator::Multiply
-        } else {
-            Operator::Add
+    let max_len = number_lines.iter().map(|l| l.len()).max().unwrap_or(0);
+    let has_content = |col: usize| {
+        number_lines
+            .iter()
+            .any(|line| line.as_bytes().get(col).is_some_and(|ch| !ch.is_ascii_whitespace()))
+    };
+
+    // Each operator marks a problem; it spans the contiguous run of columns
+    // with content around the operator
+    let mut blocks = Vec::new();
+    for (op_col_idx, op_char) in operator_line.char_indices() {
+        let operator = match op_char {
+            '*' => Operator::Multiply,
+            '+' => Operator::Add,
+            _ => continue,
         };
 
-        // Find the start and end of this problem (column range)
         let mut start_col = op_col_idx;
-        for col in (0..op_col_idx).rev() {
-            let has_content = number_lines.iter().any(|line| {
-                col < line.len() && !line.chars().nth(col).unwrap().is_whitespace()
-            });
-            if has_content {
-                start_col = col;
-            } else {
-                break;
-            }
+        while start_col > 0 && has_content(start_col - 1) {
+            start_col -= 1;
         }
-
         let mut end_col = op_col_idx;
-        let max_len = number_lines.iter().map(|l| l.len()).max().unwrap_or(0);
-        for col in (op_col_idx + 1)..max_len {
-            let has_content = number_lines.iter().any(|line| {
-                col < line.len() && !line.chars().nth(col).unwrap().is_whitespace()
-            });
-            if has_content {
-                end_col = col;
-            } else {
-                break;
-            }
+        while end_col + 1 < max_len && has_content(end_col + 1) {
+            end_col += 1;
         }
 
-        // Read numbers column-by-column from right to left
-        let mut numbers = Vec::new();
-        for col in (start_col..=end_col).rev() {
-            // Read this column top to bottom to form a number
-            let mut digits = String::new();
-            for line in number_lines {
-                if col < line.len() {
-                    let ch = line.chars().nth(col).unwrap();
-                    if ch.is_ascii_digit() {
-                        digits.push(ch);
-                    }
-                }
-            }
-            if !digits.is_empty() {
-                if let Ok(num) = digits.parse::<i64>() {
-                    numbers.push(num);
-                }
-            }
-        }
+        let rows = number_lines
+            .iter()
+            .map(|line| {
+                (start_col..=end_col)
+                    .map(|col| line.as_bytes().get(col).copied().unwrap_or(b' '))
+                    .collect()
+            })
+            .collect();
+        blocks.push(Block { operator, rows });
+    }
+
+    Ok(Worksheet { blocks })
+}
 
-        problems.push(Problem { numbers, operator });
+fn solve_problem(problem: &Problem) -> i64 {
+    match problem.operator {
+        Operator::Add => problem.numbers.iter().sum(),
+        Operator::Multiply => problem.numbers.iter().product(),
     }
+}
 
-    problems
+/// Part 1 solution
+pub fn part1(worksheet: &Worksheet) -> i64 {
+    let problems = worksheet.problems(Block::row_numbers);
+    problems.iter().map(solve_problem).sum()
 }
 
 /// Part 2 solution
-pub fn part2(input: &str) -> i64 {
-    let problems = parse_input_part2(input);
-    problems.iter().map(|p| solve_problem(p)).sum()
+pub fn part2(worksheet: &Worksheet) -> i64 {
+    let problems = worksheet.problems(Block::column_numbers);
+    problems.iter().map(solve_problem).sum()
 }
 
-/// Entry point for running this day
-pub fn run() {
-    let input = fs::read_to_string("puzzles/day06/input.txt")
-        .expect("Failed to read input file");
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Trash Compactor";
+    type Input = Worksheet;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, String> {
+        Ok(part1(input).into())
+    }
 
-    println!("Day 6: Trash Compactor");
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    fn part2(input: &Self::Input) -> Result<Answer, String> {
+        Ok(part2(input).into())
+    }
 }
 
 #[cfg(test)]
@@ -214,13 +210,30 @@ mod tests {
 
     #[test]
     fn test_part1_example1() {
-        let result = part1(EXAMPLE_INPUT);
+        let result = part1(&parse_input(EXAMPLE_INPUT).unwrap());
         assert_eq!(
             result, 4277556,
             "Example should give grand total of 4277556"
         );
     }
 
+    #[test]
+    fn test_validate_worksheet() {
+        assert_eq!(validate_worksheet(EXAMPLE_INPUT), Ok(()));
+        assert_eq!(
+            validate_worksheet("12 3\n4a 5\n*  +"),
+            Err(ParseError::new(2, 2, "expected a digit, found 'a'"))
+        );
+        assert_eq!(
+            validate_worksheet("12 3\n*  -"),
+            Err(ParseError::new(2, 4, "expected '*' or '+', found '-'"))
+        );
+        assert_eq!(
+            validate_worksheet("12 3\n    "),
+            Err(ParseError::new(2, 1, "missing operator row"))
+        );
+    }
+
     #[test]
     fn test_solve_problem_multiply() {
         let problem = Problem {
@@ -241,7 +254,7 @@ mod tests {
 
     #[test]
     fn test_parse_input() {
-        let problems = parse_input(EXAMPLE_INPUT);
+        let problems = parse_input(EXAMPLE_INPUT).unwrap().problems(Block::row_numbers);
         assert_eq!(problems.len(), 4, "Should have 4 problems");
 
         // First problem: 123 * 45 * 6
@@ -263,7 +276,7 @@ mod tests {
 
     #[test]
     fn test_part2_example1() {
-        let result = part2(EXAMPLE_INPUT);
+        let result = part2(&parse_input(EXAMPLE_INPUT).unwrap());
         assert_eq!(
             result, 3263827,
             "Part 2 example should give grand total of 3263827"
@@ -271,8 +284,8 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_input_part2() {
-        let problems = parse_input_part2(EXAMPLE_INPUT);
+    fn test_parse_input_columns() {
+        let problems = parse_input(EXAMPLE_INPUT).unwrap().problems(Block::column_numbers);
         assert_eq!(problems.len(), 4, "Should have 4 problems");
 
         // Reading right to left, column by column:
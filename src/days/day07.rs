@@ -1,32 +1,37 @@
-/// Day 7: Laboratories
-///
-/// Simulate tachyon beams in a manifold. Beams start at S, travel downward.
-/// When a beam hits a splitter (^), it stops and two new beams emerge
-/// from the left and right of the splitter.
+//! Day 7: Laboratories
+//!
+//! Simulate tachyon beams in a manifold. Beams start at S, travel downward.
+//! When a beam hits a splitter (^), it stops and two new beams emerge
+//! from the left and right of the splitter.
 
 use std::collections::HashSet;
-use std::fs;
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::utils::{parse_grid, Direction, Grid};
+
+/// The manifold grid and the position of 'S'
+type Manifold = (Grid<char>, (usize, usize));
 
 /// Parse the grid and find the start position
-fn parse_input(input: &str) -> (Vec<Vec<char>>, (usize, usize)) {
-    let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+fn parse_input(input: &str) -> Result<Manifold, ParseError> {
+    let grid = parse_grid(input, &['.', '^', 'S'])?;
 
     // Find the starting position 'S'
-    let mut start = (0, 0);
-    for (row, line) in grid.iter().enumerate() {
-        for (col, &ch) in line.iter().enumerate() {
-            if ch == 'S' {
-                start = (row, col);
-            }
+    let starts: Vec<(usize, usize)> = grid.find_all(&'S').take(2).collect();
+    let start = match starts[..] {
+        [start] => start,
+        [] => return Err(ParseError::new(1, 1, "missing start position 'S'")),
+        [_, (row, col), ..] => {
+            return Err(ParseError::new(row + 1, col + 1, "more than one start position 'S'"))
         }
-    }
-
-    (grid, start)
+    };
+    Ok((grid, start))
 }
 
 /// Simulate the tachyon beam and count splits
-fn simulate_beam(grid: &[Vec<char>], start: (usize, usize)) -> usize {
-    let rows = grid.len();
+fn simulate_beam(grid: &Grid<char>, start: (usize, usize)) -> usize {
+    let rows = grid.height();
     let mut split_count = 0;
 
     // Active beams: positions where beams are currently moving down
@@ -50,19 +55,16 @@ fn simulate_beam(grid: &[Vec<char>], start: (usize, usize)) -> usize {
         let mut next_beams: HashSet<(usize, usize)> = HashSet::new();
 
         for &(_, col) in &active_beams {
-            if col < grid[current_row].len() {
-                let ch = grid[current_row][col];
+            if let Some(&ch) = grid.get((current_row, col)) {
                 if ch == '^' {
                     // Beam hits a splitter - it stops and creates two new beams
                     split_count += 1;
 
-                    // New beam to the left (if in bounds)
-                    if col > 0 {
-                        next_beams.insert((current_row, col - 1));
-                    }
-                    // New beam to the right (if in bounds)
-                    if col + 1 < grid[current_row].len() {
-                        next_beams.insert((current_row, col + 1));
+                    // New beams to the left and right (if in bounds)
+                    for dir in [Direction::Left, Direction::Right] {
+                        if let Some(pos) = grid.step_dir((current_row, col), dir) {
+                            next_beams.insert(pos);
+                        }
                     }
                 } else {
                     // Beam continues downward
@@ -78,23 +80,21 @@ fn simulate_beam(grid: &[Vec<char>], start: (usize, usize)) -> usize {
 }
 
 /// Part 1 solution: count total number of splits
-pub fn part1(input: &str) -> usize {
-    let (grid, start) = parse_input(input);
-    simulate_beam(&grid, start)
+pub fn part1(grid: &Grid<char>, start: (usize, usize)) -> usize {
+    simulate_beam(grid, start)
 }
 
 /// Part 2 solution: Count the number of distinct timelines
 /// Each path through the manifold represents a timeline where the particle
 /// takes different left/right choices at each splitter.
-pub fn part2(input: &str) -> usize {
-    let (grid, start) = parse_input(input);
-    count_timelines(&grid, start)
+pub fn part2(grid: &Grid<char>, start: (usize, usize)) -> usize {
+    count_timelines(grid, start)
 }
 
 /// Count the number of distinct timelines (paths) through the manifold
 /// Each timeline represents a unique sequence of left/right choices at splitters
-fn count_timelines(grid: &[Vec<char>], start: (usize, usize)) -> usize {
-    let rows = grid.len();
+fn count_timelines(grid: &Grid<char>, start: (usize, usize)) -> usize {
+    let rows = grid.height();
 
     // We track (row, col, timeline_count) for each active beam position
     // Multiple timelines can be at the same position, so we track counts
@@ -114,7 +114,7 @@ fn count_timelines(grid: &[Vec<char>], start: (usize, usize)) -> usize {
             break;
         }
 
-        let row_len = grid[current_row].len() as isize;
+        let row_len = grid.width() as isize;
         let mut next_counts: HashMap<isize, usize> = HashMap::new();
 
         for (&col, &count) in &timeline_counts {
@@ -125,7 +125,7 @@ fn count_timelines(grid: &[Vec<char>], start: (usize, usize)) -> usize {
                 continue;
             }
 
-            let ch = grid[current_row][col as usize];
+            let ch = grid[(current_row, col as usize)];
             if ch == '^' {
                 // Each timeline splits into two: one goes left, one goes right
                 *next_counts.entry(col - 1).or_insert(0) += count;
@@ -145,14 +145,24 @@ fn count_timelines(grid: &[Vec<char>], start: (usize, usize)) -> usize {
     exited_timelines + timeline_counts.values().sum::<usize>()
 }
 
-/// Entry point for running this day
-pub fn run() {
-    let input = fs::read_to_string("puzzles/day07/input.txt")
-        .expect("Failed to read input file");
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "Laboratories";
+    type Input = Manifold;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, String> {
+        Ok(part1(&input.0, input.1).into())
+    }
 
-    println!("Day 7: Laboratories");
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    fn part2(input: &Self::Input) -> Result<Answer, String> {
+        Ok(part2(&input.0, input.1).into())
+    }
 }
 
 #[cfg(test)]
@@ -176,60 +186,80 @@ mod tests {
 .^.^.^.^.^...^.
 ...............";
 
+    fn run_part1(input: &str) -> usize {
+        let (grid, start) = parse_input(input).unwrap();
+        part1(&grid, start)
+    }
+
+    fn run_part2(input: &str) -> usize {
+        let (grid, start) = parse_input(input).unwrap();
+        part2(&grid, start)
+    }
+
     #[test]
     fn test_part1_example() {
-        assert_eq!(part1(EXAMPLE_INPUT), 21);
+        assert_eq!(run_part1(EXAMPLE_INPUT), 21);
     }
 
     #[test]
     fn test_parse_input() {
-        let (grid, start) = parse_input(EXAMPLE_INPUT);
+        let (grid, start) = parse_input(EXAMPLE_INPUT).unwrap();
         assert_eq!(start, (0, 7), "S should be at row 0, col 7");
-        assert_eq!(grid[0][7], 'S');
-        assert_eq!(grid[2][7], '^');
+        assert_eq!(grid[(0, 7)], 'S');
+        assert_eq!(grid[(2, 7)], '^');
+    }
+
+    #[test]
+    fn test_parse_input_errors() {
+        assert_eq!(parse_input("...\n.^."), Err(ParseError::new(1, 1, "missing start position 'S'")));
+        assert_eq!(
+            parse_input(".S.\n.^S"),
+            Err(ParseError::new(2, 3, "more than one start position 'S'"))
+        );
+        assert_eq!(parse_input(".S.\n.v.").unwrap_err().column, 2);
     }
 
     #[test]
     fn test_simple_single_split() {
         let input = "S\n.\n^";
-        assert_eq!(part1(input), 1, "Single splitter should cause 1 split");
+        assert_eq!(run_part1(input), 1, "Single splitter should cause 1 split");
     }
 
     #[test]
     fn test_no_splitters() {
         let input = "S\n.\n.";
-        assert_eq!(part1(input), 0, "No splitters means no splits");
+        assert_eq!(run_part1(input), 0, "No splitters means no splits");
     }
 
     #[test]
     fn test_two_level_split() {
         // S at center, one splitter, then two more below
         let input = "..S..\n.....\n..^..\n.....\n.^.^.";
-        assert_eq!(part1(input), 3, "Should have 3 splits: 1 at first level, 2 at second");
+        assert_eq!(run_part1(input), 3, "Should have 3 splits: 1 at first level, 2 at second");
     }
 
     #[test]
     fn test_part2_example() {
-        assert_eq!(part2(EXAMPLE_INPUT), 40);
+        assert_eq!(run_part2(EXAMPLE_INPUT), 40);
     }
 
     #[test]
     fn test_part2_simple() {
         // Single splitter: 2 timelines
         let input = "S\n.\n^";
-        assert_eq!(part2(input), 2, "Single splitter creates 2 timelines");
+        assert_eq!(run_part2(input), 2, "Single splitter creates 2 timelines");
     }
 
     #[test]
     fn test_part2_no_splitters() {
         let input = "S\n.\n.";
-        assert_eq!(part2(input), 1, "No splitters means 1 timeline");
+        assert_eq!(run_part2(input), 1, "No splitters means 1 timeline");
     }
 
     #[test]
     fn test_part2_two_sequential_splitters() {
         // Two sequential splitters (aligned): 2 * 2 = 4 timelines
         let input = ".S.\n...\n.^.\n...\n^.^";
-        assert_eq!(part2(input), 4, "Two levels of splitting creates 4 timelines");
+        assert_eq!(run_part2(input), 4, "Two levels of splitting creates 4 timelines");
     }
 }
@@ -1,42 +1,37 @@
-/// Day 9: Movie Theater
-///
-/// Find the largest rectangle that can be formed using two red tiles as opposite corners.
-/// The area of a rectangle with corners at (x1,y1) and (x2,y2) is:
-/// (|x2-x1| + 1) * (|y2-y1| + 1)
-
-use std::fs;
+//! Day 9: Movie Theater
+//!
+//! Find the largest rectangle that can be formed using two red tiles as opposite corners.
+//! The area of a rectangle with corners at (x1,y1) and (x2,y2) is:
+//! (|x2-x1| + 1) * (|y2-y1| + 1)
+
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::utils::{parse_csv_numbers, parse_lines, parse_lines_lenient, Bounds2, Point2};
+
+/// Parse a single "x,y" red tile line
+fn parse_tile(line_no: usize, line: &str) -> Result<Point2, ParseError> {
+    let parts: Vec<i64> = parse_csv_numbers(line_no, line, 2)?;
+    Ok(Point2::new(parts[0], parts[1]))
+}
 
 /// Parse input into a list of (x, y) coordinates
-fn parse_input(input: &str) -> Vec<(i64, i64)> {
-    input
-        .lines()
-        .filter_map(|line| {
-            let line = line.trim();
-            if line.is_empty() {
-                return None;
-            }
-            let parts: Vec<&str> = line.split(',').collect();
-            if parts.len() == 2 {
-                let x: i64 = parts[0].trim().parse().ok()?;
-                let y: i64 = parts[1].trim().parse().ok()?;
-                Some((x, y))
-            } else {
-                None
-            }
-        })
-        .collect()
+fn parse_input(input: &str) -> Result<Vec<Point2>, ParseError> {
+    parse_lines(input, parse_tile)
+}
+
+/// Parse coordinates, skipping lines that aren't valid tiles
+fn parse_input_lenient(input: &str) -> Vec<Point2> {
+    parse_lines_lenient(input, parse_tile)
 }
 
 /// Calculate the area of a rectangle with two opposite corners
-fn rectangle_area(p1: (i64, i64), p2: (i64, i64)) -> i64 {
-    let width = (p2.0 - p1.0).abs() + 1;
-    let height = (p2.1 - p1.1).abs() + 1;
-    width * height
+fn rectangle_area(p1: Point2, p2: Point2) -> i64 {
+    Bounds2::new(p1, p2).area()
 }
 
 /// Part 1: Find the largest rectangle area using any two red tiles as opposite corners
-pub fn part1(input: &str) -> i64 {
-    let tiles = parse_input(input);
+pub fn part1(tiles: &[Point2]) -> i64 {
     let n = tiles.len();
 
     if n < 2 {
@@ -70,23 +65,23 @@ struct EfficientPolygon {
 }
 
 impl EfficientPolygon {
-    fn from_tiles(tiles: &[(i64, i64)]) -> Self {
+    fn from_tiles(tiles: &[Point2]) -> Self {
         let mut vertical_edges = Vec::new();
 
         for i in 0..tiles.len() {
             let p1 = tiles[i];
             let p2 = tiles[(i + 1) % tiles.len()];
 
-            if p1.0 == p2.0 {
-                let y_min = p1.1.min(p2.1);
-                let y_max = p1.1.max(p2.1);
-                vertical_edges.push((p1.0, y_min, y_max));
+            if p1.x == p2.x {
+                let y_min = p1.y.min(p2.y);
+                let y_max = p1.y.max(p2.y);
+                vertical_edges.push((p1.x, y_min, y_max));
             }
         }
 
         vertical_edges.sort();
 
-        let mut y_coords: Vec<i64> = tiles.iter().map(|p| p.1).collect();
+        let mut y_coords: Vec<i64> = tiles.iter().map(|p| p.y).collect();
         y_coords.sort();
         y_coords.dedup();
 
@@ -128,11 +123,9 @@ impl EfficientPolygon {
     }
 
     /// Check if a rectangle is entirely within the polygon
-    fn contains_rectangle(&self, p1: (i64, i64), p2: (i64, i64)) -> bool {
-        let min_x = p1.0.min(p2.0);
-        let max_x = p1.0.max(p2.0);
-        let min_y = p1.1.min(p2.1);
-        let max_y = p1.1.max(p2.1);
+    fn contains_rectangle(&self, rect: Bounds2) -> bool {
+        let (min_x, min_y) = rect.min.into();
+        let (max_x, max_y) = rect.max.into();
 
         // Check corners and boundaries by sampling key y-values
         // For a rectilinear polygon, we only need to check at y-coordinates
@@ -173,8 +166,7 @@ impl EfficientPolygon {
 }
 
 /// Part 2: Find the largest rectangle using red corners, but only including red/green tiles
-pub fn part2(input: &str) -> i64 {
-    let tiles = parse_input(input);
+pub fn part2(tiles: &[Point2]) -> i64 {
     let n = tiles.len();
 
     if n < 2 {
@@ -182,15 +174,16 @@ pub fn part2(input: &str) -> i64 {
     }
 
     // Use efficient polygon for large inputs
-    let polygon = EfficientPolygon::from_tiles(&tiles);
+    let polygon = EfficientPolygon::from_tiles(tiles);
 
     let mut max_area = 0;
 
     // Check all pairs of red tiles as corners
     for i in 0..n {
         for j in (i + 1)..n {
-            if polygon.contains_rectangle(tiles[i], tiles[j]) {
-                let area = rectangle_area(tiles[i], tiles[j]);
+            let rect = Bounds2::new(tiles[i], tiles[j]);
+            if polygon.contains_rectangle(rect) {
+                let area = rect.area();
                 if area > max_area {
                     max_area = area;
                 }
@@ -201,14 +194,28 @@ pub fn part2(input: &str) -> i64 {
     max_area
 }
 
-/// Entry point for running Day 9 solutions
-pub fn run() {
-    let input = fs::read_to_string("puzzles/day09/input.txt")
-        .expect("Failed to read input file");
+pub struct Day09;
 
-    println!("Day 9: Movie Theater");
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+    const TITLE: &'static str = "Movie Theater";
+    type Input = Vec<Point2>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn parse_lenient(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(parse_input_lenient(input))
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, String> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, String> {
+        Ok(part2(input).into())
+    }
 }
 
 #[cfg(test)]
@@ -227,7 +234,8 @@ mod tests {
 7,3";
 
     // Helper functions for tests using flood-fill approach
-    fn build_colored_region(tiles: &[(i64, i64)]) -> HashSet<(i64, i64)> {
+    fn build_colored_region(tiles: &[Point2]) -> HashSet<(i64, i64)> {
+        let tiles: Vec<(i64, i64)> = tiles.iter().map(|&p| p.into()).collect();
         let mut colored: HashSet<(i64, i64)> = HashSet::new();
 
         if tiles.is_empty() {
@@ -241,7 +249,7 @@ mod tests {
             add_line_to_set(&mut colored, p1, p2);
         }
 
-        fill_interior(&mut colored, tiles)
+        fill_interior(&mut colored, &tiles)
     }
 
     fn add_line_to_set(set: &mut HashSet<(i64, i64)>, p1: (i64, i64), p2: (i64, i64)) {
@@ -318,24 +326,36 @@ mod tests {
 
     #[test]
     fn test_parse_input() {
-        let tiles = parse_input(EXAMPLE_INPUT);
+        let tiles = parse_input(EXAMPLE_INPUT).unwrap();
         assert_eq!(tiles.len(), 8);
-        assert_eq!(tiles[0], (7, 1));
-        assert_eq!(tiles[1], (11, 1));
-        assert_eq!(tiles[7], (7, 3));
+        assert_eq!(tiles[0], Point2::new(7, 1));
+        assert_eq!(tiles[1], Point2::new(11, 1));
+        assert_eq!(tiles[7], Point2::new(7, 3));
+    }
+
+    #[test]
+    fn test_parse_input_errors() {
+        assert_eq!(
+            parse_input("7,1\n11;1"),
+            Err(ParseError::new(2, 1, "expected 2 comma-separated numbers, found 1"))
+        );
+        assert_eq!(
+            parse_input_lenient("7,1\n11;1\n11,7"),
+            vec![Point2::new(7, 1), Point2::new(11, 7)]
+        );
     }
 
     #[test]
     fn test_rectangle_area_example1() {
         // Rectangle between (2,5) and (9,7) should have area 24
-        let area = rectangle_area((2, 5), (9, 7));
+        let area = rectangle_area(Point2::new(2, 5), Point2::new(9, 7));
         assert_eq!(area, 24);
     }
 
     #[test]
     fn test_rectangle_area_example2() {
         // Rectangle between (7,1) and (11,7) should have area 35
-        let area = rectangle_area((7, 1), (11, 7));
+        let area = rectangle_area(Point2::new(7, 1), Point2::new(11, 7));
         assert_eq!(area, 35);
     }
 
@@ -343,21 +363,21 @@ mod tests {
     fn test_rectangle_area_example3() {
         // Rectangle between (7,3) and (2,3) should have area 6
         // This is a thin horizontal rectangle: width=6, height=1
-        let area = rectangle_area((7, 3), (2, 3));
+        let area = rectangle_area(Point2::new(7, 3), Point2::new(2, 3));
         assert_eq!(area, 6);
     }
 
     #[test]
     fn test_rectangle_area_example4() {
         // Rectangle between (2,5) and (11,1) should have area 50
-        let area = rectangle_area((2, 5), (11, 1));
+        let area = rectangle_area(Point2::new(2, 5), Point2::new(11, 1));
         assert_eq!(area, 50);
     }
 
     #[test]
     fn test_part1_example() {
         // From puzzle: largest rectangle has area 50
-        let result = part1(EXAMPLE_INPUT);
+        let result = part1(&parse_input(EXAMPLE_INPUT).unwrap());
         assert_eq!(result, 50);
     }
 
@@ -365,28 +385,28 @@ mod tests {
     fn test_part1_two_tiles() {
         let input = "0,0\n5,5";
         // Rectangle from (0,0) to (5,5): width=6, height=6, area=36
-        assert_eq!(part1(input), 36);
+        assert_eq!(part1(&parse_input(input).unwrap()), 36);
     }
 
     #[test]
     fn test_part1_same_row() {
         let input = "0,0\n10,0";
         // Rectangle from (0,0) to (10,0): width=11, height=1, area=11
-        assert_eq!(part1(input), 11);
+        assert_eq!(part1(&parse_input(input).unwrap()), 11);
     }
 
     #[test]
     fn test_part1_same_column() {
         let input = "0,0\n0,10";
         // Rectangle from (0,0) to (0,10): width=1, height=11, area=11
-        assert_eq!(part1(input), 11);
+        assert_eq!(part1(&parse_input(input).unwrap()), 11);
     }
 
     // Part 2 tests
 
     #[test]
     fn test_build_colored_region() {
-        let tiles = parse_input(EXAMPLE_INPUT);
+        let tiles = parse_input(EXAMPLE_INPUT).unwrap();
         let colored = build_colored_region(&tiles);
 
         // Red tiles should be in the region
@@ -411,7 +431,7 @@ mod tests {
     #[test]
     fn test_part2_example() {
         // From puzzle: largest valid rectangle has area 24
-        let result = part2(EXAMPLE_INPUT);
+        let result = part2(&parse_input(EXAMPLE_INPUT).unwrap());
         assert_eq!(result, 24);
     }
 
@@ -419,20 +439,20 @@ mod tests {
     fn test_part2_rectangle_15() {
         // Rectangle between (7,3) and (11,1) should have area 15
         // and should be valid (all tiles are red/green)
-        let tiles = parse_input(EXAMPLE_INPUT);
+        let tiles = parse_input(EXAMPLE_INPUT).unwrap();
         let colored = build_colored_region(&tiles);
 
         assert!(rectangle_in_region((7, 3), (11, 1), &colored));
-        assert_eq!(rectangle_area((7, 3), (11, 1)), 15);
+        assert_eq!(rectangle_area(Point2::new(7, 3), Point2::new(11, 1)), 15);
     }
 
     #[test]
     fn test_part2_rectangle_3() {
         // Rectangle between (9,7) and (9,5) should have area 3
-        let tiles = parse_input(EXAMPLE_INPUT);
+        let tiles = parse_input(EXAMPLE_INPUT).unwrap();
         let colored = build_colored_region(&tiles);
 
         assert!(rectangle_in_region((9, 7), (9, 5), &colored));
-        assert_eq!(rectangle_area((9, 7), (9, 5)), 3);
+        assert_eq!(rectangle_area(Point2::new(9, 7), Point2::new(9, 5)), 3);
     }
 }
@@ -1,113 +1,172 @@
-/// Day 10: Factory
-///
-/// This is a "lights out" puzzle where we need to configure indicator lights
-/// by pressing buttons that toggle specific lights. This is equivalent to
-/// solving a system of linear equations over GF(2) (binary field).
-///
-/// For each machine:
-/// - Start with all lights off (0)
-/// - Need to reach target pattern shown in brackets
-/// - Each button toggles specific lights
-/// - Find minimum number of button presses
-///
-/// Algorithm: Gaussian elimination over GF(2)
-
-use std::fs;
+//! Day 10: Factory
+//!
+//! This is a "lights out" puzzle where we need to configure indicator lights
+//! by pressing buttons that toggle specific lights. This is equivalent to
+//! solving a system of linear equations over GF(2) (binary field).
+//!
+//! For each machine:
+//! - Start with all lights off (0)
+//! - Need to reach target pattern shown in brackets
+//! - Each button toggles specific lights
+//! - Find minimum number of button presses
+//!
+//! Algorithm: Gaussian elimination over GF(2), then the lightest solution
+//! among the particular solution XOR each combination of the nullspace basis
+
+use crate::answer::Answer;
+use crate::error::{parse_number, ParseError};
+use crate::solution::Solution;
+use crate::utils::gf2::{BitVec, Gf2Matrix};
+use crate::utils::ilp::{self, IlpError, IlpSolution};
+use crate::utils::parse_lines;
 
 #[derive(Debug, Clone)]
-struct Machine {
+pub struct Machine {
     target: Vec<bool>,  // Target state for each light
     buttons: Vec<Vec<usize>>,  // Which lights each button toggles
+    joltage: Vec<i64>,  // Joltage requirement for each counter
 }
 
-fn parse_input(input: &str) -> Vec<Machine> {
-    input
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| parse_machine(line))
-        .collect()
+fn parse_input(input: &str) -> Result<Vec<Machine>, ParseError> {
+    parse_lines(input, parse_machine)
+}
+
+/// Find the `open`..`close` delimited section at or after byte `from`,
+/// returning the byte range of its contents
+fn find_section(
+    line_no: usize,
+    line: &str,
+    from: usize,
+    open: char,
+    close: char,
+) -> Result<Option<(usize, usize)>, ParseError> {
+    let Some(start) = line[from..].find(open).map(|i| from + i) else {
+        return Ok(None);
+    };
+    let end = line[start..]
+        .find(close)
+        .map(|i| start + i)
+        .ok_or_else(|| ParseError::at(line_no, line, &line[start..], format!("unclosed '{}'", open)))?;
+    Ok(Some((start + 1, end)))
 }
 
-fn parse_machine(line: &str) -> Machine {
+fn parse_machine(line_no: usize, line: &str) -> Result<Machine, ParseError> {
     // Parse format: [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
 
     // Extract target pattern from [...]
-    let target_start = line.find('[').expect("No opening bracket") + 1;
-    let target_end = line.find(']').expect("No closing bracket");
+    let (target_start, target_end) = find_section(line_no, line, 0, '[', ']')?
+        .ok_or_else(|| ParseError::at(line_no, line, line.trim_start(), "missing '[' light pattern"))?;
     let target_str = &line[target_start..target_end];
-    let target: Vec<bool> = target_str.chars().map(|c| c == '#').collect();
+    let mut target = Vec::new();
+    for (idx, ch) in target_str.char_indices() {
+        match ch {
+            '#' => target.push(true),
+            '.' => target.push(false),
+            _ => {
+                return Err(ParseError::at(
+                    line_no,
+                    line,
+                    &target_str[idx..],
+                    format!("expected '.' or '#' in light pattern, found '{}'", ch),
+                ))
+            }
+        }
+    }
 
-    // Extract buttons from (...)
+    // Extract buttons from (...), up to the joltage section
+    let buttons_end = line.find('{').unwrap_or(line.len());
     let mut buttons = Vec::new();
     let mut i = target_end + 1;
-    while let Some(start) = line[i..].find('(') {
-        let start = i + start;
-        let end = line[start..].find(')').expect("No closing paren") + start;
-        let button_str = &line[start + 1..end];
+    while let Some((start, end)) = find_section(line_no, &line[..buttons_end], i, '(', ')')? {
+        let between = line[i..start - 1].trim();
+        if !between.is_empty() {
+            return Err(ParseError::at(line_no, line, between, format!("unexpected '{}'", between)));
+        }
 
-        let indices: Vec<usize> = button_str
-            .split(',')
-            .map(|s| s.trim().parse().expect("Invalid button index"))
-            .collect();
+        let mut indices: Vec<usize> = Vec::new();
+        for field in line[start..end].split(',') {
+            let index: usize = parse_number(line_no, line, field)?;
+            if index >= target.len() {
+                return Err(ParseError::at(
+                    line_no,
+                    line,
+                    field.trim(),
+                    format!("button index {} is out of range for {} lights", index, target.len()),
+                ));
+            }
+            indices.push(index);
+        }
 
         buttons.push(indices);
         i = end + 1;
     }
+    let trailing = line[i..buttons_end].trim();
+    if !trailing.is_empty() {
+        return Err(ParseError::at(line_no, line, trailing, format!("unexpected '{}'", trailing)));
+    }
 
-    Machine { target, buttons }
+    let joltage = parse_joltage_requirements(line_no, line)?;
+    if joltage.len() != target.len() {
+        return Err(ParseError::at(
+            line_no,
+            line,
+            &line[buttons_end..],
+            format!("expected {} joltage values, found {}", target.len(), joltage.len()),
+        ));
+    }
+
+    Ok(Machine { target, buttons, joltage })
 }
 
-fn parse_joltage_requirements(line: &str) -> Vec<i64> {
+fn parse_joltage_requirements(line_no: usize, line: &str) -> Result<Vec<i64>, ParseError> {
     // Extract joltage from {...}
-    let start = line.find('{').expect("No opening brace") + 1;
-    let end = line.find('}').expect("No closing brace");
-    let joltage_str = &line[start..end];
+    let (start, end) = find_section(line_no, line, 0, '{', '}')?
+        .ok_or_else(|| ParseError::new(line_no, line.chars().count() + 1, "missing '{' joltage requirements"))?;
 
-    joltage_str
+    line[start..end]
         .split(',')
-        .map(|s| s.trim().parse().expect("Invalid joltage value"))
+        .map(|field| parse_number(line_no, line, field))
         .collect()
 }
 
-fn solve_machine(machine: &Machine) -> usize {
+/// The fewest presses that light exactly the target pattern, as the set of
+/// buttons to press once each, or `None` if no combination does
+fn solve_machine(machine: &Machine) -> Option<BitVec> {
     let n_lights = machine.target.len();
-    let n_buttons = machine.buttons.len();
-
-    // Try all possible combinations of button presses (brute force for small n_buttons)
-    // This is feasible since AoC puzzles typically have reasonable button counts
-    let mut min_presses = usize::MAX;
-
-    // Try all 2^n_buttons combinations
-    for mask in 0_u32..(1 << n_buttons) {
-        let mut state = vec![false; n_lights];
-
-        // Apply each button that's set in the mask
-        for button_idx in 0..n_buttons {
-            if mask & (1 << button_idx) != 0 {
-                // Press this button
-                for &light_idx in &machine.buttons[button_idx] {
-                    state[light_idx] = !state[light_idx];
-                }
-            }
-        }
 
-        // Check if we reached the target
-        if state == machine.target {
-            let presses = mask.count_ones() as usize;
-            min_presses = min_presses.min(presses);
-        }
-    }
+    // Column `j` holds the lights toggled by button `j`
+    let columns: Vec<BitVec> = machine
+        .buttons
+        .iter()
+        .map(|button| {
+            let mut column = BitVec::zeros(n_lights);
+            for &light in button {
+                column.toggle(light);
+            }
+            column
+        })
+        .collect();
+    let target: BitVec = machine.target.iter().copied().collect();
 
-    min_presses
+    Gf2Matrix::from_columns(n_lights, &columns).min_weight_solution(&target)
 }
 
-
-pub fn part1(input: &str) -> usize {
-    let machines = parse_input(input);
-    machines.iter().map(|m| solve_machine(m)).sum()
+/// Total presses over every machine, or which machine can't be configured
+pub fn part1(machines: &[Machine]) -> Result<usize, String> {
+    machines
+        .iter()
+        .enumerate()
+        .map(|(idx, machine)| {
+            solve_machine(machine)
+                .map(|presses| presses.count_ones())
+                .ok_or_else(|| format!("machine {}: light pattern is unsolvable", idx + 1))
+        })
+        .sum()
 }
 
-fn solve_machine_joltage(buttons: &[Vec<usize>], joltage_targets: &[i64]) -> i64 {
+/// The fewest presses that bring every counter to its target, with the
+/// number of times to press each button
+fn solve_machine_joltage(buttons: &[Vec<usize>], joltage_targets: &[i64]) -> Result<IlpSolution, IlpError> {
     let n_counters = joltage_targets.len();
     let n_buttons = buttons.len();
 
@@ -121,231 +180,41 @@ fn solve_machine_joltage(buttons: &[Vec<usize>], joltage_targets: &[i64]) -> i64
         }
     }
 
-    // Use simplex-like approach: find a feasible solution, then optimize
-    // Since this is integer programming with equality constraints, we can use
-    // Gaussian elimination to find the solution space, then search for minimum
-
-    solve_min_sum_ilp(&coeff, joltage_targets)
+    // Every press costs the same, so minimise the total number of presses
+    ilp::minimize(&coeff, joltage_targets, &vec![1; n_buttons])
 }
 
-/// Solve using Gaussian elimination to reduce the system, then enumerate over free variables
-fn solve_min_sum_ilp(coeff: &[Vec<i64>], targets: &[i64]) -> i64 {
-    let n_counters = coeff.len();
-    let n_buttons = if n_counters > 0 { coeff[0].len() } else { return 0; };
-
-    // Convert to rational arithmetic for exact computation
-    // Create augmented matrix [A | b]
-    type Rat = (i64, i64); // (numerator, denominator)
-
-    fn gcd(a: i64, b: i64) -> i64 {
-        if b == 0 { a.abs() } else { gcd(b, a % b) }
-    }
-
-    fn rat_reduce(r: Rat) -> Rat {
-        if r.0 == 0 { return (0, 1); }
-        let g = gcd(r.0, r.1);
-        let (n, d) = (r.0 / g, r.1 / g);
-        if d < 0 { (-n, -d) } else { (n, d) }
-    }
-
-    fn rat_sub(a: Rat, b: Rat) -> Rat {
-        rat_reduce((a.0 * b.1 - b.0 * a.1, a.1 * b.1))
-    }
-
-    fn rat_mul(a: Rat, b: Rat) -> Rat {
-        rat_reduce((a.0 * b.0, a.1 * b.1))
-    }
-
-    fn rat_div(a: Rat, b: Rat) -> Rat {
-        rat_mul(a, (b.1, b.0))
-    }
-
-    // Build augmented matrix
-    let mut aug: Vec<Vec<Rat>> = vec![vec![(0, 1); n_buttons + 1]; n_counters];
-    for i in 0..n_counters {
-        for j in 0..n_buttons {
-            aug[i][j] = (coeff[i][j], 1);
-        }
-        aug[i][n_buttons] = (targets[i], 1);
-    }
-
-    // Gaussian elimination with partial pivoting
-    let mut pivot_cols = Vec::new();
-    let mut row = 0;
-
-    for col in 0..n_buttons {
-        if row >= n_counters { break; }
-
-        // Find pivot
-        let mut pivot_row = None;
-        for r in row..n_counters {
-            if aug[r][col].0 != 0 {
-                pivot_row = Some(r);
-                break;
-            }
-        }
-
-        let pivot_row = match pivot_row {
-            Some(r) => r,
-            None => continue, // No pivot in this column
-        };
-
-        // Swap rows
-        aug.swap(row, pivot_row);
-        pivot_cols.push(col);
-
-        // Scale pivot row
-        let pivot = aug[row][col];
-        for j in col..=n_buttons {
-            aug[row][j] = rat_div(aug[row][j], pivot);
-        }
-
-        // Eliminate
-        for r in 0..n_counters {
-            if r != row && aug[r][col].0 != 0 {
-                let factor = aug[r][col];
-                for j in col..=n_buttons {
-                    aug[r][j] = rat_sub(aug[r][j], rat_mul(factor, aug[row][j]));
-                }
-            }
-        }
-
-        row += 1;
-    }
-
-    // Now we have row echelon form
-    // pivot_cols contains the basic variables
-    // Free variables are all other columns
-
-    let free_cols: Vec<usize> = (0..n_buttons).filter(|c| !pivot_cols.contains(c)).collect();
-    let n_free = free_cols.len();
-
-    // For each assignment of free variables, compute basic variables
-    // Basic variable i (in pivot_cols[i]) = aug[i][n_buttons] - sum(aug[i][free_j] * free_j)
-
-    // If no free variables, we have a unique solution
-    if n_free == 0 {
-        let mut result = vec![0i64; n_buttons];
-        for (i, &col) in pivot_cols.iter().enumerate() {
-            let (n, d) = aug[i][n_buttons];
-            if d != 1 || n < 0 { return 0; } // Non-integer or negative
-            result[col] = n;
-        }
-        return result.iter().sum();
-    }
-
-    // With free variables, we need to search
-    // Limit the search space by bounding free variables
-    let max_target = *targets.iter().max().unwrap_or(&0);
-
-    let mut best = i64::MAX;
-    let mut free_values = vec![0i64; n_free];
-
-    search_free_vars(&aug, &pivot_cols, &free_cols, n_buttons, targets,
-                     &mut free_values, 0, max_target, &mut best);
-
-    if best == i64::MAX { 0 } else { best }
+/// Total presses over every machine, or which machine can't be configured
+pub fn part2(machines: &[Machine]) -> Result<i64, String> {
+    machines
+        .iter()
+        .enumerate()
+        .map(|(idx, machine)| {
+            solve_machine_joltage(&machine.buttons, &machine.joltage)
+                .map(|solution| solution.objective)
+                .map_err(|err| format!("machine {}: {}", idx + 1, err))
+        })
+        .sum()
 }
 
-fn search_free_vars(
-    aug: &[Vec<(i64, i64)>],
-    pivot_cols: &[usize],
-    free_cols: &[usize],
-    n_buttons: usize,
-    targets: &[i64],
-    free_values: &mut [i64],
-    idx: usize,
-    max_val: i64,
-    best: &mut i64
-) {
-    let n_free = free_cols.len();
-
-    if idx == n_free {
-        // Compute basic variables
-        let mut result = vec![0i64; n_buttons];
-
-        // Set free variables
-        for (i, &col) in free_cols.iter().enumerate() {
-            result[col] = free_values[i];
-        }
-
-        // Compute basic variables
-        for (i, &col) in pivot_cols.iter().enumerate() {
-            let (rhs_n, rhs_d) = aug[i][n_buttons];
-            let mut val_n = rhs_n;
-            let mut val_d = rhs_d;
-
-            // Subtract contributions from free variables
-            for (j, &free_col) in free_cols.iter().enumerate() {
-                let (coef_n, coef_d) = aug[i][free_col];
-                // val -= coef * free_values[j]
-                // val_n/val_d -= (coef_n/coef_d) * free_values[j]
-                let sub_n = coef_n * free_values[j];
-                let sub_d = coef_d;
-                // val_n/val_d - sub_n/sub_d = (val_n * sub_d - sub_n * val_d) / (val_d * sub_d)
-                val_n = val_n * sub_d - sub_n * val_d;
-                val_d = val_d * sub_d;
-                // Reduce
-                let g = gcd_helper(val_n, val_d);
-                val_n /= g;
-                val_d /= g;
-                if val_d < 0 { val_n = -val_n; val_d = -val_d; }
-            }
-
-            // Check if integer
-            if val_d != 1 && val_d != -1 {
-                if val_n % val_d != 0 { return; }
-                val_n /= val_d;
-            } else if val_d == -1 {
-                val_n = -val_n;
-            }
+pub struct Day10;
 
-            if val_n < 0 { return; } // Negative solution
-            result[col] = val_n;
-        }
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    const TITLE: &'static str = "Factory";
+    type Input = Vec<Machine>;
 
-        let sum: i64 = result.iter().sum();
-        if sum < *best {
-            *best = sum;
-        }
-        return;
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
     }
 
-    // Determine max value for this free variable
-    // Based on ensuring basic variables stay non-negative
-    let max_for_this = max_val; // Use full target range
-
-    for val in 0..=max_for_this {
-        free_values[idx] = val;
-        search_free_vars(aug, pivot_cols, free_cols, n_buttons, targets,
-                        free_values, idx + 1, max_val, best);
+    fn part1(input: &Self::Input) -> Result<Answer, String> {
+        part1(input).map(Answer::from)
     }
-    free_values[idx] = 0;
-}
-
-fn gcd_helper(a: i64, b: i64) -> i64 {
-    if b == 0 { a.abs() } else { gcd_helper(b, a % b) }
-}
 
-pub fn part2(input: &str) -> i64 {
-    input
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| {
-            let machine = parse_machine(line);
-            let joltage = parse_joltage_requirements(line);
-            solve_machine_joltage(&machine.buttons, &joltage)
-        })
-        .sum()
-}
-
-pub fn run() {
-    let input = fs::read_to_string("puzzles/day10/input.txt")
-        .expect("Failed to read input file");
-
-    println!("Day 10: Factory");
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    fn part2(input: &Self::Input) -> Result<Answer, String> {
+        part2(input).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
@@ -358,14 +227,14 @@ mod tests {
 
     #[test]
     fn test_part1_example() {
-        let result = part1(EXAMPLE_INPUT);
-        assert_eq!(result, 7, "Example should give 2+3+2=7");
+        let result = part1(&parse_input(EXAMPLE_INPUT).unwrap());
+        assert_eq!(result, Ok(7), "Example should give 2+3+2=7");
     }
 
     #[test]
     fn test_parse_machine() {
         let line = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}";
-        let machine = parse_machine(line);
+        let machine = parse_machine(1, line).unwrap();
 
         assert_eq!(machine.target, vec![false, true, true, false]);
         assert_eq!(machine.buttons.len(), 6);
@@ -373,67 +242,137 @@ mod tests {
         assert_eq!(machine.buttons[1], vec![1, 3]);
     }
 
+    #[test]
+    fn test_parse_machine_errors() {
+        assert_eq!(
+            parse_machine(3, ".##. (3) {1}").unwrap_err(),
+            ParseError::new(3, 1, "missing '[' light pattern")
+        );
+        assert_eq!(
+            parse_machine(1, "[.#x.] (3) {1,2,3,4}").unwrap_err(),
+            ParseError::new(1, 4, "expected '.' or '#' in light pattern, found 'x'")
+        );
+        assert_eq!(
+            parse_machine(1, "[.##.] (3) (1,4) {1,2,3,4}").unwrap_err(),
+            ParseError::new(1, 15, "button index 4 is out of range for 4 lights")
+        );
+        assert_eq!(
+            parse_machine(1, "[.##.] (3) (1,3 {1,2,3,4}").unwrap_err(),
+            ParseError::new(1, 12, "unclosed '('")
+        );
+        assert_eq!(
+            parse_machine(1, "[.##.] (3) {1,2,3}").unwrap_err(),
+            ParseError::new(1, 12, "expected 4 joltage values, found 3")
+        );
+        assert_eq!(
+            parse_machine(1, "[.##.] (3)").unwrap_err(),
+            ParseError::new(1, 11, "missing '{' joltage requirements")
+        );
+    }
+
     #[test]
     fn test_solve_first_machine() {
         let line = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}";
-        let machine = parse_machine(line);
-        let result = solve_machine(&machine);
+        let machine = parse_machine(1, line).unwrap();
+        let result = solve_machine(&machine).unwrap().count_ones();
         assert_eq!(result, 2, "First machine needs 2 button presses");
     }
 
     #[test]
     fn test_solve_second_machine() {
         let line = "[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}";
-        let machine = parse_machine(line);
-        let result = solve_machine(&machine);
+        let machine = parse_machine(1, line).unwrap();
+        let result = solve_machine(&machine).unwrap().count_ones();
         assert_eq!(result, 3, "Second machine needs 3 button presses");
     }
 
     #[test]
     fn test_solve_third_machine() {
         let line = "[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
-        let machine = parse_machine(line);
-        let result = solve_machine(&machine);
+        let machine = parse_machine(1, line).unwrap();
+        let result = solve_machine(&machine).unwrap().count_ones();
         assert_eq!(result, 2, "Third machine needs 2 button presses");
     }
 
+    #[test]
+    fn test_solve_many_buttons() {
+        // 40 buttons overflow a u32 mask; button i toggles lights i and i+1
+        let n = 40;
+        let buttons: Vec<String> = (0..n).map(|i| format!("({},{})", i, i + 1)).collect();
+        let line = format!("[#{}#] {} {{{}}}", ".".repeat(n - 1), buttons.join(" "), vec!["0"; n + 1].join(","));
+        let machine = parse_machine(1, &line).unwrap();
+        let presses = solve_machine(&machine).unwrap();
+        assert_eq!(presses.count_ones(), n, "every button flips the parity along the chain");
+    }
+
+    #[test]
+    fn test_lights_unsolvable() {
+        // Both buttons toggle both lights, so they can't differ
+        let machines = parse_input("[...#] (3) {1,1,1,1}\n[#.] (0,1) (0,1) {1,1}").unwrap();
+        assert_eq!(part1(&machines), Err("machine 2: light pattern is unsolvable".to_string()));
+    }
+
     #[test]
     fn test_parse_joltage() {
         let line = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}";
-        let joltage = parse_joltage_requirements(line);
+        let joltage = parse_joltage_requirements(1, line).unwrap();
         assert_eq!(joltage, vec![3, 5, 4, 7]);
     }
 
     #[test]
     fn test_part2_example() {
-        let result = part2(EXAMPLE_INPUT);
-        assert_eq!(result, 33, "Example should give 10+12+11=33");
+        let result = part2(&parse_input(EXAMPLE_INPUT).unwrap());
+        assert_eq!(result, Ok(33), "Example should give 10+12+11=33");
     }
 
     #[test]
     fn test_solve_first_machine_joltage() {
         let line = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}";
-        let machine = parse_machine(line);
-        let joltage = parse_joltage_requirements(line);
-        let result = solve_machine_joltage(&machine.buttons, &joltage);
-        assert_eq!(result, 10, "First machine joltage needs 10 button presses");
+        let machine = parse_machine(1, line).unwrap();
+        let joltage = parse_joltage_requirements(1, line).unwrap();
+        let result = solve_machine_joltage(&machine.buttons, &joltage).unwrap();
+        assert_eq!(result.objective, 10, "First machine joltage needs 10 button presses");
     }
 
     #[test]
     fn test_solve_second_machine_joltage() {
         let line = "[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}";
-        let machine = parse_machine(line);
-        let joltage = parse_joltage_requirements(line);
-        let result = solve_machine_joltage(&machine.buttons, &joltage);
-        assert_eq!(result, 12, "Second machine joltage needs 12 button presses");
+        let machine = parse_machine(1, line).unwrap();
+        let joltage = parse_joltage_requirements(1, line).unwrap();
+        let result = solve_machine_joltage(&machine.buttons, &joltage).unwrap();
+        assert_eq!(result.objective, 12, "Second machine joltage needs 12 button presses");
     }
 
     #[test]
     fn test_solve_third_machine_joltage() {
         let line = "[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
-        let machine = parse_machine(line);
-        let joltage = parse_joltage_requirements(line);
-        let result = solve_machine_joltage(&machine.buttons, &joltage);
-        assert_eq!(result, 11, "Third machine joltage needs 11 button presses");
+        let machine = parse_machine(1, line).unwrap();
+        let joltage = parse_joltage_requirements(1, line).unwrap();
+        let result = solve_machine_joltage(&machine.buttons, &joltage).unwrap();
+        assert_eq!(result.objective, 11, "Third machine joltage needs 11 button presses");
+    }
+
+    #[test]
+    fn test_joltage_presses_witness() {
+        let line = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}";
+        let machine = parse_machine(1, line).unwrap();
+        let solution = solve_machine_joltage(&machine.buttons, &machine.joltage).unwrap();
+
+        // Pressing each button as often as the witness says hits every target
+        let mut counters = vec![0; machine.joltage.len()];
+        for (button, &presses) in machine.buttons.iter().zip(&solution.x) {
+            for &counter in button {
+                counters[counter] += presses;
+            }
+        }
+        assert_eq!(counters, machine.joltage);
+        assert_eq!(solution.x.iter().sum::<i64>(), 10);
+    }
+
+    #[test]
+    fn test_joltage_unreachable() {
+        // No button raises the second counter
+        let machines = parse_input("[..] (0) {1,1}").unwrap();
+        assert_eq!(part2(&machines), Err("machine 1: no solution satisfies the constraints".to_string()));
     }
 }
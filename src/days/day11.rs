@@ -1,35 +1,44 @@
-/// Day 11: Reactor
-///
-/// Find all paths from `you` to `out` in a directed graph.
-/// Each line defines a device and its outputs.
+//! Day 11: Reactor
+//!
+//! Find all paths from `you` to `out` in a directed graph.
+//! Each line defines a device and its outputs.
 
 use std::collections::HashMap;
-use std::fs;
-
-fn parse_input(input: &str) -> HashMap<String, Vec<String>> {
-    let mut graph: HashMap<String, Vec<String>> = HashMap::new();
-
-    for line in input.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::utils::{parse_lines, parse_lines_lenient};
+
+/// Parse a "device: output output ..." line
+fn parse_device(line_no: usize, line: &str) -> Result<(String, Vec<String>), ParseError> {
+    let trimmed = line.trim();
+    let (from, outputs) = trimmed
+        .split_once(':')
+        .ok_or_else(|| ParseError::at(line_no, line, trimmed, "expected 'device: outputs'"))?;
+
+    let from = from.trim();
+    if from.is_empty() {
+        return Err(ParseError::at(line_no, line, trimmed, "missing device name before ':'"));
+    }
+    if let Some(idx) = outputs.find(':') {
+        return Err(ParseError::at(line_no, line, &outputs[idx..], "unexpected second ':'"));
+    }
 
-        let parts: Vec<&str> = line.split(':').collect();
-        if parts.len() != 2 {
-            continue;
-        }
+    let outputs: Vec<String> = outputs
+        .split_whitespace()
+        .map(|s| s.to_string())
+        .collect();
 
-        let from = parts[0].trim().to_string();
-        let outputs: Vec<String> = parts[1]
-            .split_whitespace()
-            .map(|s| s.to_string())
-            .collect();
+    Ok((from.to_string(), outputs))
+}
 
-        graph.insert(from, outputs);
-    }
+fn parse_input(input: &str) -> Result<HashMap<String, Vec<String>>, ParseError> {
+    Ok(parse_lines(input, parse_device)?.into_iter().collect())
+}
 
-    graph
+/// Parse the graph, skipping lines that aren't valid device definitions
+fn parse_input_lenient(input: &str) -> HashMap<String, Vec<String>> {
+    parse_lines_lenient(input, parse_device).into_iter().collect()
 }
 
 fn count_paths(
@@ -59,10 +68,9 @@ fn count_paths(
     count
 }
 
-pub fn part1(input: &str) -> u64 {
-    let graph = parse_input(input);
+pub fn part1(graph: &HashMap<String, Vec<String>>) -> u64 {
     let mut memo = HashMap::new();
-    count_paths(&graph, "you", "out", &mut memo)
+    count_paths(graph, "you", "out", &mut memo)
 }
 
 fn count_paths_with_required(
@@ -105,20 +113,34 @@ fn count_paths_with_required(
     count
 }
 
-pub fn part2(input: &str) -> u64 {
-    let graph = parse_input(input);
+pub fn part2(graph: &HashMap<String, Vec<String>>) -> u64 {
     let mut memo = HashMap::new();
     let must_visit = &["dac", "fft"];
-    count_paths_with_required(&graph, "svr", "out", must_visit, 0, &mut memo)
+    count_paths_with_required(graph, "svr", "out", must_visit, 0, &mut memo)
 }
 
-pub fn run() {
-    let input = fs::read_to_string("puzzles/day11/input.txt")
-        .expect("Failed to read input file");
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    const TITLE: &'static str = "Reactor";
+    type Input = HashMap<String, Vec<String>>;
 
-    println!("Day 11: Reactor");
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn parse_lenient(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(parse_input_lenient(input))
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, String> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, String> {
+        Ok(part2(input).into())
+    }
 }
 
 #[cfg(test)]
@@ -139,22 +161,35 @@ iii: out
 
     #[test]
     fn test_part1_example() {
-        let result = part1(EXAMPLE_INPUT);
+        let result = part1(&parse_input(EXAMPLE_INPUT).unwrap());
         assert_eq!(result, 5, "Should find 5 paths from you to out");
     }
 
     #[test]
     fn test_parse_input() {
-        let graph = parse_input(EXAMPLE_INPUT);
+        let graph = parse_input(EXAMPLE_INPUT).unwrap();
         assert_eq!(graph.get("you"), Some(&vec!["bbb".to_string(), "ccc".to_string()]));
         assert_eq!(graph.get("bbb"), Some(&vec!["ddd".to_string(), "eee".to_string()]));
     }
 
+    #[test]
+    fn test_parse_input_errors() {
+        assert_eq!(
+            parse_input("a: b\nb out\n"),
+            Err(ParseError::new(2, 1, "expected 'device: outputs'"))
+        );
+        assert_eq!(
+            parse_input("a: b: c\n"),
+            Err(ParseError::new(1, 5, "unexpected second ':'"))
+        );
+        assert_eq!(parse_input_lenient("a: b\nb out\nb: out\n").len(), 2);
+    }
+
     #[test]
     fn test_count_simple_path() {
         // Simple chain: a -> b -> out
         let input = "a: b\nb: out\n";
-        let graph = parse_input(input);
+        let graph = parse_input(input).unwrap();
         let mut memo = HashMap::new();
         let count = count_paths(&graph, "a", "out", &mut memo);
         assert_eq!(count, 1);
@@ -164,7 +199,7 @@ iii: out
     fn test_count_branching_paths() {
         // Branching: a -> b, c -> out
         let input = "a: b c\nb: out\nc: out\n";
-        let graph = parse_input(input);
+        let graph = parse_input(input).unwrap();
         let mut memo = HashMap::new();
         let count = count_paths(&graph, "a", "out", &mut memo);
         assert_eq!(count, 2);
@@ -187,7 +222,7 @@ hhh: out
 
     #[test]
     fn test_part2_example() {
-        let result = part2(EXAMPLE_INPUT_PART2);
+        let result = part2(&parse_input(EXAMPLE_INPUT_PART2).unwrap());
         assert_eq!(result, 2, "Should find 2 paths from svr to out visiting both dac and fft");
     }
 }
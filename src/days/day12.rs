@@ -1,326 +1,333 @@
-/// Day 12: Christmas Tree Farm
-///
-/// Bin packing puzzle: determine if polyomino-like shapes can fit into a grid.
-/// Shapes can be rotated and flipped. Count how many regions can fit all their presents.
-
-use std::collections::HashSet;
-use std::fs;
-
-/// A shape is represented as a set of (row, col) offsets from an origin
-type Shape = Vec<(i32, i32)>;
-
-/// Parse a shape from its visual representation
-fn parse_shape(shape_str: &str) -> Shape {
-    let mut coords = Vec::new();
-    for (row, line) in shape_str.lines().enumerate() {
-        for (col, ch) in line.chars().enumerate() {
-            if ch == '#' {
-                coords.push((row as i32, col as i32));
-            }
-        }
-    }
-    // Normalize: shift so minimum row and col are 0
-    if coords.is_empty() {
-        return coords;
-    }
-    let min_row = coords.iter().map(|&(r, _)| r).min().unwrap();
-    let min_col = coords.iter().map(|&(_, c)| c).min().unwrap();
-    coords.iter().map(|&(r, c)| (r - min_row, c - min_col)).collect()
+//! Day 12: Christmas Tree Farm
+//!
+//! Bin packing puzzle: determine if polyomino-like shapes can fit into a grid.
+//! Shapes can be rotated and flipped. Count how many regions can fit all their presents.
+//!
+//! Algorithm: exact cover with dancing links, where each cell is covered by a
+//! present or left as one of the region's spare cells
+
+use crate::answer::Answer;
+use crate::error::{parse_number, ParseError};
+use crate::solution::Solution;
+use crate::utils::dlx::ExactCover;
+use crate::utils::polyomino::{self, Polyomino, Transform};
+use crate::utils::Grid;
+
+/// A region to fill: (width, height, count of each shape)
+type Region = (usize, usize, Vec<usize>);
+
+/// Parse a region line like "4x4: 0 0 0 0 2 0"
+fn parse_region(line_no: usize, line: &str) -> Result<Region, ParseError> {
+    let (dims, counts) = line
+        .split_once(':')
+        .ok_or_else(|| ParseError::at(line_no, line, line.trim_start(), "expected 'WxH: counts'"))?;
+    let (width, height) = dims.split_once('x').ok_or_else(|| {
+        ParseError::at(line_no, line, dims.trim_start(), format!("expected dimensions like '4x4', found '{}'", dims.trim()))
+    })?;
+    let width: usize = parse_number(line_no, line, width)?;
+    let height: usize = parse_number(line_no, line, height)?;
+    let counts: Vec<usize> = counts
+        .split_whitespace()
+        .map(|s| parse_number(line_no, line, s))
+        .collect::<Result<_, _>>()?;
+    Ok((width, height, counts))
 }
 
-/// Generate all 8 orientations (4 rotations x 2 flips) of a shape
-fn all_orientations(shape: &Shape) -> Vec<Shape> {
-    let mut orientations = HashSet::new();
-    let mut current = shape.clone();
-
-    for _ in 0..4 {
-        // Add current orientation
-        let normalized = normalize(&current);
-        orientations.insert(normalized.clone());
-
-        // Add flipped version
-        let flipped: Shape = current.iter().map(|&(r, c)| (r, -c)).collect();
-        let normalized_flip = normalize(&flipped);
-        orientations.insert(normalized_flip);
+/// Parse the shape library, then one region per line
+fn parse_input(input: &str) -> Result<(Vec<Polyomino>, Vec<Region>), ParseError> {
+    let (shapes, first_region) = polyomino::parse_library(input)?;
 
-        // Rotate 90 degrees clockwise: (r, c) -> (c, -r)
-        current = current.iter().map(|&(r, c)| (c, -r)).collect();
-    }
-
-    orientations.into_iter().collect()
-}
-
-/// Normalize a shape so its minimum row and col are 0, and sort for consistent comparison
-fn normalize(shape: &Shape) -> Shape {
-    if shape.is_empty() {
-        return shape.clone();
-    }
-    let min_row = shape.iter().map(|&(r, _)| r).min().unwrap();
-    let min_col = shape.iter().map(|&(_, c)| c).min().unwrap();
-    let mut normalized: Shape = shape.iter().map(|&(r, c)| (r - min_row, c - min_col)).collect();
-    normalized.sort();
-    normalized
-}
-
-/// Parse the full input into shapes and regions
-fn parse_input(input: &str) -> (Vec<Vec<Shape>>, Vec<(usize, usize, Vec<usize>)>) {
-    let mut shapes: Vec<Vec<Shape>> = Vec::new();
     let mut regions = Vec::new();
-    let mut current_shape_lines = Vec::new();
-    let mut in_shape = false;
-
-    for line in input.lines() {
-        // Check for shape header like "0:" or "5:"
-        if line.chars().next().map(|c| c.is_ascii_digit()).unwrap_or(false)
-           && line.contains(':')
-           && !line.contains('x') {
-            // Finalize previous shape if any
-            if in_shape && !current_shape_lines.is_empty() {
-                let shape_str = current_shape_lines.join("\n");
-                let base_shape = parse_shape(&shape_str);
-                shapes.push(all_orientations(&base_shape));
-                current_shape_lines.clear();
-            }
-            in_shape = true;
-        } else if line.contains('x') && line.contains(':') {
-            // This is a region line like "4x4: 0 0 0 0 2 0"
-            // Finalize previous shape if any
-            if in_shape && !current_shape_lines.is_empty() {
-                let shape_str = current_shape_lines.join("\n");
-                let base_shape = parse_shape(&shape_str);
-                shapes.push(all_orientations(&base_shape));
-                current_shape_lines.clear();
-            }
-            in_shape = false;
-
-            // Parse region
-            let line_parts: Vec<&str> = line.split(':').collect();
-            let dims: Vec<&str> = line_parts[0].split('x').collect();
-            let width: usize = dims[0].trim().parse().unwrap();
-            let height: usize = dims[1].trim().parse().unwrap();
-            let counts: Vec<usize> = line_parts[1]
-                .split_whitespace()
-                .map(|s| s.parse().unwrap())
-                .collect();
-            regions.push((width, height, counts));
-        } else if in_shape && (line.contains('#') || line.contains('.')) {
-            current_shape_lines.push(line);
+    for (idx, line) in input.lines().enumerate().skip(first_region) {
+        let line_no = idx + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if !line.contains(':') {
+            return Err(ParseError::at(line_no, line, line.trim_start(), "expected a shape header or region"));
+        }
+        let region = parse_region(line_no, line)?;
+        if region.2.len() > shapes.len() {
+            return Err(ParseError::new(
+                line_no,
+                1,
+                format!("region lists {} shape counts but only {} shapes are defined", region.2.len(), shapes.len()),
+            ));
         }
-        // Skip empty lines and other content
+        regions.push(region);
     }
 
-    // Handle last shape if any (shouldn't happen if regions follow shapes)
-    if in_shape && !current_shape_lines.is_empty() {
-        let shape_str = current_shape_lines.join("\n");
-        let base_shape = parse_shape(&shape_str);
-        shapes.push(all_orientations(&base_shape));
-    }
+    Ok((shapes, regions))
+}
 
-    (shapes, regions)
+/// Where one present goes: which shape, how it is turned, and the grid cell
+/// its turned shape's offsets are measured from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub shape: usize,
+    pub transform: Transform,
+    pub row: usize,
+    pub col: usize,
 }
 
-/// Check if a shape can be placed at a given position on the grid
-fn can_place(grid: &[Vec<bool>], shape: &Shape, start_row: i32, start_col: i32, width: usize, height: usize) -> bool {
-    for &(dr, dc) in shape {
-        let r = start_row + dr;
-        let c = start_col + dc;
-        if r < 0 || c < 0 || r >= height as i32 || c >= width as i32 {
-            return false;
-        }
-        if grid[r as usize][c as usize] {
-            return false;
-        }
+/// Place every requested present in a `width x height` region, leaving any
+/// cells over as gaps, or `None` if it can't be done
+///
+/// Regions too small by area, or roomy enough to give each present its own
+/// bounding box, are settled without searching. Anything else becomes an
+/// exact cover problem whose exhausted search proves there's no packing.
+pub fn pack(shapes: &[Polyomino], width: usize, height: usize, counts: &[usize]) -> Option<Vec<Placement>> {
+    let needed: usize = counts.iter().zip(shapes).map(|(&count, shape)| shape.area() * count).sum();
+    let area = width * height;
+    if needed > area {
+        return None;
     }
-    true
+    pack_in_boxes(shapes, width, height, counts).or_else(|| pack_exact(shapes, width, height, counts, area - needed))
 }
 
-/// Place a shape on the grid
-fn place_shape(grid: &mut [Vec<bool>], shape: &Shape, start_row: i32, start_col: i32) {
-    for &(dr, dc) in shape {
-        let r = (start_row + dr) as usize;
-        let c = (start_col + dc) as usize;
-        grid[r][c] = true;
+/// Give each present its own box as large as the widest and tallest shape,
+/// lying every shape down so it is at least as wide as it is tall
+fn pack_in_boxes(shapes: &[Polyomino], width: usize, height: usize, counts: &[usize]) -> Option<Vec<Placement>> {
+    let used: Vec<(usize, Transform, Polyomino)> = counts
+        .iter()
+        .enumerate()
+        .filter(|&(_, &count)| count > 0)
+        .map(|(idx, _)| {
+            let (transform, shape) = shapes[idx]
+                .orientations()
+                .into_iter()
+                .find(|(_, shape)| shape.width() >= shape.height())
+                .expect("rotating a shape a quarter turn swaps its width and height");
+            (idx, transform, shape)
+        })
+        .collect();
+    let box_width = used.iter().map(|(_, _, shape)| shape.width()).max().unwrap_or(1).max(1);
+    let box_height = used.iter().map(|(_, _, shape)| shape.height()).max().unwrap_or(1).max(1);
+    let per_row = width / box_width;
+    let boxes = per_row * (height / box_height);
+    let presents: usize = counts.iter().sum();
+    if presents > boxes {
+        return None;
     }
-}
 
-/// Remove a shape from the grid
-fn remove_shape(grid: &mut [Vec<bool>], shape: &Shape, start_row: i32, start_col: i32) {
-    for &(dr, dc) in shape {
-        let r = (start_row + dr) as usize;
-        let c = (start_col + dc) as usize;
-        grid[r][c] = false;
+    let mut placements = Vec::with_capacity(presents);
+    for (shape, transform, _) in used {
+        for _ in 0..counts[shape] {
+            let slot = placements.len();
+            placements.push(Placement {
+                shape,
+                transform,
+                row: slot / per_row * box_height,
+                col: slot % per_row * box_width,
+            });
+        }
     }
+    Some(placements)
 }
 
-/// Build a list of all pieces we need to place
-fn build_pieces(counts: &[usize]) -> Vec<usize> {
-    let mut pieces = Vec::new();
-    for (shape_idx, &count) in counts.iter().enumerate() {
-        for _ in 0..count {
-            pieces.push(shape_idx);
+/// Pack by exact cover: every cell is covered once, by a present or by one
+/// of the `gaps` cells left over, and each shape is placed `counts` times
+///
+/// Congruent shapes share a column, so swapping their presents around is
+/// never searched, and each shape is only tried in its distinct orientations.
+fn pack_exact(
+    shapes: &[Polyomino],
+    width: usize,
+    height: usize,
+    counts: &[usize],
+    gaps: usize,
+) -> Option<Vec<Placement>> {
+    // The first shape index of each congruence class, with the class total
+    let mut classes: Vec<(usize, usize)> = Vec::new();
+    for (idx, &count) in counts.iter().enumerate().filter(|&(_, &count)| count > 0) {
+        match classes.iter_mut().find(|(rep, _)| shapes[*rep].is_congruent(&shapes[idx])) {
+            Some((_, total)) => *total += count,
+            None => classes.push((idx, count)),
         }
     }
-    pieces
-}
 
-/// Find the first empty cell in the grid (for more efficient search)
-fn find_first_empty(grid: &[Vec<bool>]) -> Option<(usize, usize)> {
-    for (r, row) in grid.iter().enumerate() {
-        for (c, &cell) in row.iter().enumerate() {
-            if !cell {
-                return Some((r, c));
+    // Columns: one per cell, one per class, then the gap budget
+    let cells = width * height;
+    let gap_column = cells + classes.len();
+    let mut problem = ExactCover::new(gap_column + 1, 0);
+    problem.set_multiplicity(gap_column, gaps);
+
+    let mut placements = Vec::new();
+    let mut columns = Vec::new();
+    for (class, &(rep, total)) in classes.iter().enumerate() {
+        problem.set_multiplicity(cells + class, total);
+        for (transform, shape) in shapes[rep].orientations() {
+            for row in 0..(height + 1).saturating_sub(shape.height()) {
+                for col in 0..(width + 1).saturating_sub(shape.width()) {
+                    columns.clear();
+                    columns.extend(shape.cells().iter().map(|p| (row + p.y as usize) * width + col + p.x as usize));
+                    columns.push(cells + class);
+                    problem.add_row(&columns);
+                    placements.push(Placement { shape: rep, transform, row, col });
+                }
             }
         }
     }
-    None
+    // Any cell may instead be left empty, as long as the budget allows
+    if gaps > 0 {
+        for cell in 0..cells {
+            problem.add_row(&[cell, gap_column]);
+        }
+    }
+
+    let rows = problem.solve()?;
+    let mut left = counts.to_vec();
+    let packing = rows
+        .into_iter()
+        .filter_map(|row| placements.get(row).copied())
+        .map(|placement| {
+            // Hand the present to a shape of its class still short of its count
+            let rep = &shapes[placement.shape];
+            let shape = (placement.shape..shapes.len())
+                .find(|&idx| left.get(idx).is_some_and(|&n| n > 0) && shapes[idx].is_congruent(rep))
+                .expect("a class covers exactly its shapes' counts");
+            left[shape] -= 1;
+            let transform = shapes[shape]
+                .transform_to(&rep.transform(placement.transform))
+                .expect("congruent shapes turn into each other");
+            Placement { shape, transform, ..placement }
+        })
+        .collect();
+    Some(packing)
 }
 
-/// Try to solve the packing problem using backtracking
-fn solve(
-    grid: &mut Vec<Vec<bool>>,
-    shapes: &[Vec<Shape>],
-    remaining: &mut Vec<usize>,
-    width: usize,
-    height: usize,
-) -> bool {
-    solve_inner(grid, shapes, remaining, width, height)
+/// The cells a placed present covers, as signed `(row, col)` pairs so that
+/// a bad placement can be reported rather than wrapping around
+fn covered_cells(shape: &Polyomino, placement: Placement) -> Vec<(isize, isize)> {
+    let (row, col) = (placement.row as isize, placement.col as isize);
+    shape
+        .transform(placement.transform)
+        .cells()
+        .iter()
+        .map(|p| (row + p.y as isize, col + p.x as isize))
+        .collect()
 }
 
-fn solve_inner(
-    grid: &mut Vec<Vec<bool>>,
-    shapes: &[Vec<Shape>],
-    remaining: &mut Vec<usize>,
+/// Check a packing without trusting how it was found: every placement names
+/// a real shape, stays inside the region and overlaps no other, and each
+/// shape is placed exactly as often as `counts` asks
+pub fn check_packing(
+    shapes: &[Polyomino],
     width: usize,
     height: usize,
-) -> bool {
-    if remaining.is_empty() {
-        return true;
+    counts: &[usize],
+    placements: &[Placement],
+) -> Result<(), String> {
+    let mut owner: Grid<Option<usize>> = Grid::new(width, height, None);
+    let mut placed = vec![0; shapes.len()];
+    for (idx, placement) in placements.iter().enumerate() {
+        let shape = shapes
+            .get(placement.shape)
+            .ok_or_else(|| format!("placement {} uses unknown shape {}", idx, placement.shape))?;
+        for (row, col) in covered_cells(shape, *placement) {
+            let pos = owner
+                .position(row, col)
+                .ok_or_else(|| format!("placement {} leaves the region at ({}, {})", idx, row, col))?;
+            if let Some(other) = owner[pos].replace(idx) {
+                return Err(format!("placements {} and {} overlap at ({}, {})", other, idx, row, col));
+            }
+        }
+        placed[placement.shape] += 1;
     }
 
-    // Find the first empty cell - we'll try to place shapes that cover it
-    // or mark it as permanently empty and move on
-    let first_empty = find_first_empty(grid);
-
-    let (target_r, target_c) = match first_empty {
-        Some(pos) => pos,
-        None => {
-            // No empty cells left but still have pieces - can't fit
-            return remaining.is_empty();
+    for (shape, &count) in placed.iter().enumerate() {
+        let wanted = counts.get(shape).copied().unwrap_or(0);
+        if count != wanted {
+            return Err(format!("shape {} placed {} times, expected {}", shape, count, wanted));
         }
-    };
-
-    // Count remaining cells needed
-    let cells_needed: usize = remaining.iter()
-        .map(|&idx| shapes[idx][0].len())
-        .sum();
-
-    // Count empty cells remaining
-    let empty_cells: usize = grid.iter()
-        .flat_map(|row| row.iter())
-        .filter(|&&cell| !cell)
-        .count();
-
-    // If we need more cells than available, fail early
-    if cells_needed > empty_cells {
-        return false;
     }
+    Ok(())
+}
 
-    // Try each remaining piece type (deduplicate identical shapes)
-    let mut tried_shapes = std::collections::HashSet::new();
-    for piece_idx in 0..remaining.len() {
-        let shape_idx = remaining[piece_idx];
-        if !tried_shapes.insert(shape_idx) {
-            continue;
+/// Draw a packing with each present as a letter and gaps as `.`
+///
+/// Presents are lettered `A`-`Z` then `a`-`z` in placement order, starting
+/// over after 52. Assumes the packing passes `check_packing`.
+pub fn render_packing(shapes: &[Polyomino], width: usize, height: usize, placements: &[Placement]) -> Grid<char> {
+    const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+    let mut grid = Grid::new(width, height, '.');
+    for (idx, placement) in placements.iter().enumerate() {
+        let letter = LETTERS[idx % LETTERS.len()] as char;
+        for (row, col) in covered_cells(&shapes[placement.shape], *placement) {
+            grid[(row as usize, col as usize)] = letter;
         }
+    }
+    grid
+}
 
-        // Try each orientation
-        for orientation in &shapes[shape_idx] {
-            // Try to place the shape so it covers the first empty cell
-            for &(dr, dc) in orientation.iter() {
-                let start_row = target_r as i32 - dr;
-                let start_col = target_c as i32 - dc;
-
-                if can_place(grid, orientation, start_row, start_col, width, height) {
-                    place_shape(grid, orientation, start_row, start_col);
-                    let removed = remaining.remove(piece_idx);
-
-                    if solve_inner(grid, shapes, remaining, width, height) {
-                        remaining.insert(piece_idx, removed);
-                        remove_shape(grid, orientation, start_row, start_col);
-                        return true;
-                    }
-
-                    remaining.insert(piece_idx, removed);
-                    remove_shape(grid, orientation, start_row, start_col);
-                }
-            }
+/// Number of regions that fit their presents, checking each packing found
+pub fn part1(shapes: &[Polyomino], regions: &[Region]) -> Result<usize, String> {
+    let mut fits = 0;
+    for (idx, (width, height, counts)) in regions.iter().enumerate() {
+        if let Some(placements) = pack(shapes, *width, *height, counts) {
+            check_packing(shapes, *width, *height, counts, &placements)
+                .map_err(|err| format!("region {}: {}", idx + 1, err))?;
+            fits += 1;
         }
     }
-
-    // No piece could be placed to cover the first empty cell
-    // Mark this cell as "permanently empty" by filling it, then continue
-    grid[target_r][target_c] = true;
-    let result = solve_inner(grid, shapes, remaining, width, height);
-    grid[target_r][target_c] = false;
-
-    result
+    Ok(fits)
 }
 
-/// Check if a region can fit all the required pieces
-fn can_fit(shapes: &[Vec<Shape>], width: usize, height: usize, counts: &[usize]) -> bool {
-    // Quick check: total cells
-    let total_shape_cells: usize = counts.iter().enumerate()
-        .map(|(idx, &count)| {
-            if count == 0 || idx >= shapes.len() || shapes[idx].is_empty() {
-                0
-            } else {
-                shapes[idx][0].len() * count
-            }
-        })
-        .sum();
+pub struct Day12;
 
-    if total_shape_cells > width * height {
-        return false;
-    }
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+    const TITLE: &'static str = "Christmas Tree Farm";
+    type Input = (Vec<Polyomino>, Vec<Region>);
 
-    let mut grid = vec![vec![false; width]; height];
-    let mut remaining = build_pieces(counts);
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
 
-    // Sort pieces by size (largest first) for better pruning
-    remaining.sort_by(|&a, &b| {
-        let size_a = if shapes[a].is_empty() { 0 } else { shapes[a][0].len() };
-        let size_b = if shapes[b].is_empty() { 0 } else { shapes[b][0].len() };
-        size_b.cmp(&size_a)
-    });
+    fn part1(input: &Self::Input) -> Result<Answer, String> {
+        part1(&input.0, &input.1).map(Answer::from)
+    }
 
-    solve(&mut grid, shapes, &mut remaining, width, height)
+    fn part2(_input: &Self::Input) -> Result<Answer, String> {
+        // Day 12 is the final puzzle of AoC 2025 and has no second part
+        Ok(Answer::NotApplicable)
+    }
 }
 
-pub fn part1(input: &str) -> usize {
-    let (shapes, regions) = parse_input(input);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::Point2;
 
-    regions.iter()
-        .filter(|(width, height, counts)| can_fit(&shapes, *width, *height, counts))
-        .count()
-}
+    /// The offset of a cell at `(row, col)`
+    fn at(row: i32, col: i32) -> Point2<i32> {
+        Point2::new(col, row)
+    }
 
-pub fn part2(input: &str) -> usize {
-    // Day 12 Part 2 uses the same answer as Part 1 (final puzzle of AoC 2025)
-    part1(input)
-}
+    /// A shape from `(row, col)` cells
+    fn cells(cells: &[(i32, i32)]) -> Polyomino {
+        Polyomino::new(cells.iter().map(|&(row, col)| at(row, col)))
+    }
 
-pub fn run() {
-    let input = fs::read_to_string("puzzles/day12/input.txt")
-        .expect("Failed to read input file");
+    /// Check if a region can fit all the required pieces
+    fn can_fit(shapes: &[Polyomino], width: usize, height: usize, counts: &[usize]) -> bool {
+        pack(shapes, width, height, counts).is_some()
+    }
 
-    println!("Day 12: Christmas Tree Farm");
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
+    /// Check if a shape can be placed at a given position on the grid
+    fn can_place(grid: &Grid<bool>, shape: &Polyomino, start_row: i32, start_col: i32) -> bool {
+        shape.cells().iter().all(|p| {
+            grid.position((start_row + p.y) as isize, (start_col + p.x) as isize)
+                .is_some_and(|pos| !grid[pos])
+        })
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Mark the cells covered by a shape as filled
+    fn set_shape(grid: &mut Grid<bool>, shape: &Polyomino, start_row: i32, start_col: i32) {
+        for p in shape.cells() {
+            grid[((start_row + p.y) as usize, (start_col + p.x) as usize)] = true;
+        }
+    }
 
     const EXAMPLE_INPUT: &str = "0:
 ###
@@ -360,28 +367,28 @@ mod tests {
     #[test]
     fn test_parse_shape() {
         let shape_str = "###\n#..\n###";
-        let shape = parse_shape(shape_str);
-        assert_eq!(shape.len(), 7);
-        assert!(shape.contains(&(0, 0)));
-        assert!(shape.contains(&(0, 1)));
-        assert!(shape.contains(&(0, 2)));
-        assert!(shape.contains(&(1, 0)));
-        assert!(shape.contains(&(2, 0)));
-        assert!(shape.contains(&(2, 1)));
-        assert!(shape.contains(&(2, 2)));
+        let shape: Polyomino = shape_str.parse().unwrap();
+        assert_eq!(shape.area(), 7);
+        assert!(shape.contains(at(0, 0)));
+        assert!(shape.contains(at(0, 1)));
+        assert!(shape.contains(at(0, 2)));
+        assert!(shape.contains(at(1, 0)));
+        assert!(shape.contains(at(2, 0)));
+        assert!(shape.contains(at(2, 1)));
+        assert!(shape.contains(at(2, 2)));
     }
 
     #[test]
     fn test_all_orientations() {
-        let shape = vec![(0, 0), (0, 1), (1, 0)]; // L-shape
-        let orientations = all_orientations(&shape);
+        let shape = cells(&[(0, 0), (0, 1), (1, 0)]); // L-shape
+        let orientations = shape.orientations();
         // L-shape should have 4 distinct orientations
-        assert!(orientations.len() >= 4);
+        assert_eq!(orientations.len(), 4);
     }
 
     #[test]
     fn test_parse_input() {
-        let (shapes, regions) = parse_input(EXAMPLE_INPUT);
+        let (shapes, regions) = parse_input(EXAMPLE_INPUT).unwrap();
         assert_eq!(shapes.len(), 6);
         assert_eq!(regions.len(), 3);
         assert_eq!(regions[0], (4, 4, vec![0, 0, 0, 0, 2, 0]));
@@ -389,6 +396,30 @@ mod tests {
         assert_eq!(regions[2], (12, 5, vec![1, 0, 1, 0, 3, 2]));
     }
 
+    #[test]
+    fn test_parse_input_errors() {
+        assert_eq!(
+            parse_input("0:\n#x\n"),
+            Err(ParseError::new(2, 2, "expected '#' or '.' in shape, found 'x'"))
+        );
+        assert_eq!(
+            parse_input("0:\n##\n\n4x4: 1 two\n"),
+            Err(ParseError::new(4, 8, "invalid number 'two'"))
+        );
+        assert_eq!(
+            parse_input("0:\n##\n\n4x4: 1 1\n").unwrap_err().message,
+            "region lists 2 shape counts but only 1 shapes are defined"
+        );
+        assert_eq!(
+            parse_input("hello\n"),
+            Err(ParseError::new(1, 1, "expected a shape header or region"))
+        );
+        assert_eq!(
+            parse_input("0:\n#\n\n2:\n#\n"),
+            Err(ParseError::new(4, 1, "expected shape 1, found shape 2"))
+        );
+    }
+
     #[test]
     fn test_manual_solution() {
         // Manually verify that shape 4 can fit in the expected positions
@@ -398,84 +429,55 @@ mod tests {
         // ABAB
         // .BBB
 
-        let (shapes, _) = parse_input(EXAMPLE_INPUT);
+        let (shapes, _) = parse_input(EXAMPLE_INPUT).unwrap();
 
         // Shape A should have orientation 2: [(0, 0), (0, 1), (0, 2), (1, 0), (1, 2), (2, 0), (2, 2)]
         // This matches A's cells when placed at (0,0)
-        let shape_a_expected: Shape = vec![(0,0),(0,1),(0,2),(1,0),(1,2),(2,0),(2,2)];
+        let shape_a_expected = cells(&[(0,0),(0,1),(0,2),(1,0),(1,2),(2,0),(2,2)]);
 
         // Shape B relative coords (from placing at 1,1):
         // B cells: (1,1),(1,3),(2,1),(2,3),(3,1),(3,2),(3,3)
         // Normalized: (0,0),(0,2),(1,0),(1,2),(2,0),(2,1),(2,2)
-        let shape_b_expected: Shape = vec![(0,0),(0,2),(1,0),(1,2),(2,0),(2,1),(2,2)];
-
-        let mut found_a = false;
-        let mut found_b = false;
-        for orient in shapes[4].iter() {
-            let mut sorted = orient.clone();
-            sorted.sort();
-
-            let mut a_sorted = shape_a_expected.clone();
-            a_sorted.sort();
-            if sorted == a_sorted {
-                found_a = true;
-            }
+        let shape_b_expected = cells(&[(0,0),(0,2),(1,0),(1,2),(2,0),(2,1),(2,2)]);
 
-            let mut b_sorted = shape_b_expected.clone();
-            b_sorted.sort();
-            if sorted == b_sorted {
-                found_b = true;
-            }
-        }
+        let orientations: Vec<Polyomino> = shapes[4].orientations().into_iter().map(|(_, shape)| shape).collect();
+        let found_a = orientations.contains(&shape_a_expected);
+        let found_b = orientations.contains(&shape_b_expected);
         assert!(found_a, "Should find orientation matching A's shape");
         assert!(found_b, "Should find orientation matching B's shape");
 
         // Now manually test if the shapes can be placed
-        let mut grid = vec![vec![false; 4]; 4];
+        let mut grid = Grid::new(4, 4, false);
 
         // Find A's orientation and place it at (0,0)
-        let orient_a = shapes[4].iter().find(|o| {
-            let mut sorted = (*o).clone();
-            sorted.sort();
-            let mut expected = shape_a_expected.clone();
-            expected.sort();
-            sorted == expected
-        }).unwrap();
+        let orient_a = orientations.iter().find(|&o| *o == shape_a_expected).unwrap();
 
-        assert!(can_place(&grid, orient_a, 0, 0, 4, 4), "A should be placeable at (0,0)");
-        place_shape(&mut grid, orient_a, 0, 0);
+        assert!(can_place(&grid, orient_a, 0, 0), "A should be placeable at (0,0)");
+        set_shape(&mut grid, orient_a, 0, 0);
 
         println!("After placing A:");
-        for row in &grid {
-            println!("{:?}", row);
-        }
+        println!("{}", grid.map(|&filled| if filled { '#' } else { '.' }));
 
         // Find B's orientation and place it at (1,1)
-        let orient_b = shapes[4].iter().find(|o| {
-            let mut sorted = (*o).clone();
-            sorted.sort();
-            let mut expected = shape_b_expected.clone();
-            expected.sort();
-            sorted == expected
-        }).unwrap();
+        let orient_b = orientations.iter().find(|&o| *o == shape_b_expected).unwrap();
 
         println!("Trying to place B at (1,1) with orientation {:?}", orient_b);
-        assert!(can_place(&grid, orient_b, 1, 1, 4, 4), "B should be placeable at (1,1)");
+        assert!(can_place(&grid, orient_b, 1, 1), "B should be placeable at (1,1)");
     }
 
     #[test]
     fn test_example_region1() {
         // 4x4 with 2 copies of shape 4
-        let (shapes, _) = parse_input(EXAMPLE_INPUT);
+        let (shapes, _) = parse_input(EXAMPLE_INPUT).unwrap();
 
         // Debug: print shape 4
-        println!("Shape 4 has {} orientations", shapes[4].len());
-        for (i, orient) in shapes[4].iter().enumerate() {
-            println!("Orientation {}: {:?}", i, orient);
+        println!("Shape 4 has {} orientations", shapes[4].orientations().len());
+        for (transform, orient) in shapes[4].orientations() {
+            println!("Orientation {:?}:\n{}", transform, orient);
         }
 
         // Shape 4 should have 7 cells (###, #.., ###)
-        let shape4_cells = shapes[4][0].len();
+        let shape4_cells = shapes[4].area();
         println!("Shape 4 has {} cells", shape4_cells);
 
         // Total cells needed = 2 * 7 = 14, available = 4 * 4 = 16
@@ -488,7 +490,7 @@ mod tests {
     #[test]
     fn test_example_region2() {
         // 12x5 with shapes 0, 2, 4x2, 5x2
-        let (shapes, _) = parse_input(EXAMPLE_INPUT);
+        let (shapes, _) = parse_input(EXAMPLE_INPUT).unwrap();
         let can = can_fit(&shapes, 12, 5, &[1, 0, 1, 0, 2, 2]);
         assert!(can, "Region 2 should be able to fit the pieces");
     }
@@ -496,13 +498,87 @@ mod tests {
     #[test]
     fn test_example_region3() {
         // 12x5 with shapes 0, 2, 4x3, 5x2 - should NOT fit
-        let (shapes, _) = parse_input(EXAMPLE_INPUT);
+        let (shapes, _) = parse_input(EXAMPLE_INPUT).unwrap();
         let can = can_fit(&shapes, 12, 5, &[1, 0, 1, 0, 3, 2]);
         assert!(!can, "Region 3 should NOT be able to fit the pieces");
     }
 
+    #[test]
+    fn test_pack_places_every_present() {
+        let (shapes, _) = parse_input(EXAMPLE_INPUT).unwrap();
+        let counts = [1, 0, 1, 0, 2, 2];
+        let placements = pack(&shapes, 12, 5, &counts).unwrap();
+        assert_eq!(check_packing(&shapes, 12, 5, &counts, &placements), Ok(()));
+
+        let grid = render_packing(&shapes, 12, 5, &placements);
+        for letter in "ABCDEF".chars() {
+            assert_eq!(grid.find_all(&letter).count(), 7, "present {} has 7 cells", letter);
+        }
+        assert_eq!(grid.find_all(&'.').count(), 60 - 6 * 7);
+    }
+
+    #[test]
+    fn test_render_manual_solution() {
+        let (shapes, _) = parse_input(EXAMPLE_INPUT).unwrap();
+        let orientation = |cells_of: &[(i32, i32)]| shapes[4].transform_to(&cells(cells_of)).unwrap();
+        let a = orientation(&[(0,0),(0,1),(0,2),(1,0),(1,2),(2,0),(2,2)]);
+        let b = orientation(&[(0,0),(0,2),(1,0),(1,2),(2,0),(2,1),(2,2)]);
+        let placements = [
+            Placement { shape: 4, transform: a, row: 0, col: 0 },
+            Placement { shape: 4, transform: b, row: 1, col: 1 },
+        ];
+
+        assert_eq!(check_packing(&shapes, 4, 4, &[0, 0, 0, 0, 2, 0], &placements), Ok(()));
+        assert_eq!(render_packing(&shapes, 4, 4, &placements).to_string(), "AAA.\nABAB\nABAB\n.BBB");
+    }
+
+    #[test]
+    fn test_check_packing_errors() {
+        let (shapes, _) = parse_input(EXAMPLE_INPUT).unwrap();
+        let at = |row, col| Placement { shape: 4, transform: Transform::IDENTITY, row, col };
+        let counts = [0, 0, 0, 0, 2, 0];
+
+        assert_eq!(
+            check_packing(&shapes, 4, 4, &counts, &[at(0, 0), at(0, 0)]),
+            Err("placements 0 and 1 overlap at (0, 0)".to_string())
+        );
+        assert_eq!(
+            check_packing(&shapes, 4, 4, &counts, &[at(0, 0), at(3, 0)]),
+            Err("placement 1 leaves the region at (4, 0)".to_string())
+        );
+        assert_eq!(
+            check_packing(&shapes, 4, 4, &counts, &[at(0, 0)]),
+            Err("shape 4 placed 1 times, expected 2".to_string())
+        );
+        assert_eq!(
+            check_packing(&shapes, 4, 4, &counts, &[Placement { shape: 9, ..at(0, 0) }]),
+            Err("placement 0 uses unknown shape 9".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pack_congruent_shapes() {
+        // S and Z are mirror images, so they share a column in the search
+        let (shapes, regions) = parse_input("0:\n##.\n.##\n\n1:\n.##\n##.\n\n2:\n##.\n.##\n\n4x4: 1 1 1\n").unwrap();
+        let (width, height, counts) = &regions[0];
+        let placements = pack(&shapes, *width, *height, counts).unwrap();
+        assert_eq!(check_packing(&shapes, *width, *height, counts, &placements), Ok(()));
+        let rendered = render_packing(&shapes, *width, *height, &placements).to_string();
+        assert_eq!(rendered, "AA..\nBAAC\nBBCC\n.BC.");
+    }
+
+    #[test]
+    fn test_pack_large_regions() {
+        let (shapes, _) = parse_input(EXAMPLE_INPUT).unwrap();
+        // A 3x3 box each for 400 presents
+        assert_eq!(pack(&shapes, 60, 60, &[100, 100, 50, 50, 50, 50]).map(|p| p.len()), Some(400));
+        // 550 presents of 7 cells can't fit in 3600 cells
+        assert_eq!(pack(&shapes, 60, 60, &[0, 0, 0, 0, 550, 0]), None);
+    }
+
     #[test]
     fn test_part1_example() {
-        assert_eq!(part1(EXAMPLE_INPUT), 2);
+        let (shapes, regions) = parse_input(EXAMPLE_INPUT).unwrap();
+        assert_eq!(part1(&shapes, &regions), Ok(2));
     }
 }
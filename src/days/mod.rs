@@ -1,12 +1,17 @@
-/// Module containing all daily Advent of Code solutions
-///
-/// Each day's solution is in its own module file (day01.rs, day02.rs, etc.)
-/// and follows the same structure:
-///
-/// - Public `part1()` and `part2()` functions that solve each part
-/// - Public `run()` function that reads input and prints results
-/// - Comprehensive tests based on puzzle examples
-/// - Helper functions for parsing and solving
+//! Module containing all daily Advent of Code solutions
+//!
+//! Each day's solution is in its own module file (day01.rs, day02.rs, etc.)
+//! and follows the same structure:
+//!
+//! - A unit struct (e.g. `Day01`) implementing [`Solution`](crate::solution::Solution)
+//! - Public `part1()` and `part2()` functions that solve each part from the
+//!   parsed model, so the input is parsed once and shared by both
+//! - Comprehensive tests based on puzzle examples
+//! - Helper functions for parsing and solving
+//!
+//! New days must also be added to [`ALL`] so the runner can find them.
+
+use crate::solution::Puzzle;
 
 pub mod day01;
 pub mod day02;
@@ -21,5 +26,65 @@ pub mod day10;
 pub mod day11;
 pub mod day12;
 
-// Uncomment as more days are implemented
-// ... and so on
+/// Registry of every implemented day, in day order
+pub static ALL: &[&dyn Puzzle] = &[
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+    &day04::Day04,
+    &day05::Day05,
+    &day06::Day06,
+    &day07::Day07,
+    &day08::Day08,
+    &day09::Day09,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+];
+
+/// Look up a day's solution by number
+pub fn get(day: u8) -> Option<&'static dyn Puzzle> {
+    ALL.iter().copied().find(|puzzle| puzzle.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::Answer;
+
+    #[test]
+    fn test_registry_in_day_order() {
+        let days: Vec<u8> = ALL.iter().map(|puzzle| puzzle.day()).collect();
+        assert_eq!(days, (1..=12).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_get() {
+        assert_eq!(get(8).map(|puzzle| puzzle.title()), Some("Playground"));
+        assert!(get(13).is_none());
+    }
+
+    #[test]
+    fn test_parse_and_solve_through_registry() {
+        let puzzle = get(1).unwrap();
+        let input = puzzle.parse("L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82").unwrap();
+        assert_eq!(puzzle.part1(&input), Ok(Answer::Signed(3)));
+        assert_eq!(puzzle.part2(&input), Ok(Answer::Signed(6)));
+    }
+
+    #[test]
+    fn test_day12_has_no_part2() {
+        let puzzle = get(12).unwrap();
+        let input = puzzle.parse("0:\n#\n\n1x1: 1\n").unwrap();
+        assert_eq!(puzzle.part1(&input), Ok(Answer::Unsigned(1)));
+        assert_eq!(puzzle.part2(&input), Ok(Answer::NotApplicable));
+    }
+
+    #[test]
+    fn test_parse_errors_surface_through_registry() {
+        let puzzle = get(1).unwrap();
+        let err = puzzle.parse("L68\nbogus").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert!(puzzle.parse_lenient("L68\nbogus").is_ok());
+    }
+}
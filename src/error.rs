@@ -0,0 +1,88 @@
+//! Error type shared by every day's parser
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A parse failure, pointing at the offending position in the puzzle input
+///
+/// Line and column are 1-based so they match what an editor shows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        ParseError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Build an error for `token`, which must be a slice of `line`
+    ///
+    /// The column is derived from where `token` starts within `line`.
+    pub fn at(line_no: usize, line: &str, token: &str, message: impl Into<String>) -> Self {
+        ParseError::new(line_no, column_of(line, token), message)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// 1-based column at which `token` starts within `line`
+///
+/// Falls back to column 1 if `token` is not a slice of `line`.
+pub fn column_of(line: &str, token: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let offset = (token.as_ptr() as usize).wrapping_sub(start);
+    match line.get(..offset) {
+        Some(prefix) => prefix.chars().count() + 1,
+        None => 1,
+    }
+}
+
+/// Parse `token` (a slice of `line`) as a number, reporting where it failed
+pub fn parse_number<T: FromStr>(line_no: usize, line: &str, token: &str) -> Result<T, ParseError> {
+    let trimmed = token.trim();
+    trimmed
+        .parse()
+        .map_err(|_| ParseError::at(line_no, line, trimmed, format!("invalid number '{}'", trimmed)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = ParseError::new(3, 7, "unexpected character 'x'");
+        assert_eq!(err.to_string(), "line 3, column 7: unexpected character 'x'");
+    }
+
+    #[test]
+    fn test_column_of() {
+        let line = "12,34,56";
+        assert_eq!(column_of(line, &line[0..2]), 1);
+        assert_eq!(column_of(line, &line[3..5]), 4);
+        assert_eq!(column_of(line, "elsewhere"), 1);
+    }
+
+    #[test]
+    fn test_parse_number() {
+        let line = "5, x7";
+        assert_eq!(parse_number::<i64>(2, line, &line[..1]), Ok(5));
+        assert_eq!(
+            parse_number::<i64>(2, line, &line[2..]),
+            Err(ParseError::new(2, 4, "invalid number 'x7'"))
+        );
+    }
+}
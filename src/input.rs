@@ -0,0 +1,172 @@
+//! Locating and reading puzzle input
+//!
+//! By default a day's input lives at `puzzles/dayNN/input.txt`, with the
+//! examples from the puzzle description stored alongside it as
+//! `puzzles/dayNN/example1.txt`, `example2.txt`, ... The puzzles root can be
+//! moved with `--puzzles <dir>` or the `AOC_PUZZLES_DIR` environment variable.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Puzzles root used when neither the CLI nor the environment sets one
+pub const DEFAULT_PUZZLES_DIR: &str = "puzzles";
+
+/// Environment variable overriding the puzzles root
+pub const PUZZLES_DIR_ENV: &str = "AOC_PUZZLES_DIR";
+
+/// Where a day's input should come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// The day's real input under the puzzles root
+    Puzzle,
+    /// The Nth (1-based) example stored under the puzzles root
+    Example(usize),
+    /// An explicit file
+    File(PathBuf),
+    /// Standard input
+    Stdin,
+}
+
+impl InputSource {
+    /// Whether this source names the same text for every day
+    pub fn is_shared(&self) -> bool {
+        matches!(self, InputSource::File(_) | InputSource::Stdin)
+    }
+}
+
+/// Failure to read a day's input
+#[derive(Debug)]
+pub struct InputError {
+    /// The file (or `<stdin>`) that could not be read
+    pub location: String,
+    pub error: io::Error,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.error)
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Resolves an [`InputSource`] to text for a given day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputLoader {
+    pub source: InputSource,
+    pub puzzles_dir: PathBuf,
+}
+
+impl InputLoader {
+    /// Create a loader, falling back to `AOC_PUZZLES_DIR` and then
+    /// `puzzles/` when no root is given
+    pub fn new(source: InputSource, puzzles_dir: Option<PathBuf>) -> Self {
+        let puzzles_dir = puzzles_dir
+            .or_else(|| env::var_os(PUZZLES_DIR_ENV).map(PathBuf::from))
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_PUZZLES_DIR));
+        InputLoader {
+            source,
+            puzzles_dir,
+        }
+    }
+
+    /// Directory holding a day's puzzle files
+    pub fn day_dir(&self, day: u8) -> PathBuf {
+        self.puzzles_dir.join(format!("day{:02}", day))
+    }
+
+    /// The file this loader reads for `day`, or `None` for stdin
+    pub fn path(&self, day: u8) -> Option<PathBuf> {
+        match &self.source {
+            InputSource::Puzzle => Some(self.day_dir(day).join("input.txt")),
+            InputSource::Example(n) => Some(self.day_dir(day).join(format!("example{}.txt", n))),
+            InputSource::File(path) => Some(path.clone()),
+            InputSource::Stdin => None,
+        }
+    }
+
+    /// Read the input for `day`
+    pub fn read(&self, day: u8) -> Result<String, InputError> {
+        match self.path(day) {
+            Some(path) => read_file(&path),
+            None => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|error| InputError {
+                        location: "<stdin>".to_string(),
+                        error,
+                    })?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+/// 64-bit FNV-1a hash of an input, to tell which input produced a result
+pub fn hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn read_file(path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|error| InputError {
+        location: path.display().to_string(),
+        error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loader(source: InputSource) -> InputLoader {
+        InputLoader::new(source, Some(PathBuf::from("/data/aoc")))
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(
+            loader(InputSource::Puzzle).path(3),
+            Some(PathBuf::from("/data/aoc/day03/input.txt"))
+        );
+        assert_eq!(
+            loader(InputSource::Example(2)).path(12),
+            Some(PathBuf::from("/data/aoc/day12/example2.txt"))
+        );
+        assert_eq!(
+            loader(InputSource::File(PathBuf::from("mine.txt"))).path(5),
+            Some(PathBuf::from("mine.txt"))
+        );
+        assert_eq!(loader(InputSource::Stdin).path(5), None);
+    }
+
+    #[test]
+    fn test_read_file() {
+        let path = env::temp_dir().join(format!("aoc-2025-input-{}.txt", std::process::id()));
+        fs::write(&path, "L68\n").unwrap();
+        assert_eq!(
+            loader(InputSource::File(path.clone())).read(1).unwrap(),
+            "L68\n"
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(hash("L68\n"), hash("L68"));
+    }
+
+    #[test]
+    fn test_read_missing_file() {
+        let err = loader(InputSource::Puzzle).read(1).unwrap_err();
+        assert_eq!(err.location, "/data/aoc/day01/input.txt");
+        assert_eq!(err.error.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -1,40 +1,228 @@
-/// Advent of Code 2025 - Main Entry Point
-///
-/// This is a simple runner for executing individual day solutions.
-/// In the automated workflow, this will be called by the orchestration scripts.
+//! Advent of Code 2025 - Main Entry Point
+//!
+//! This is a simple runner for executing individual day solutions.
+//! In the automated workflow, this will be called by the orchestration scripts.
 
-use aoc_2025::days;
+use aoc_2025::bench::{self, Baseline};
+use aoc_2025::cli::{self, Cli, Command};
+use aoc_2025::input::InputLoader;
+use aoc_2025::output;
+use aoc_2025::pool;
+use aoc_2025::runner::{self, DayReport, RunError};
+use aoc_2025::solution::Puzzle;
+use aoc_2025::verify::{self, Check};
+use aoc_2025::{days, report};
 use std::env;
+use std::fs;
+use std::io;
+use std::process;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    if args.len() < 2 {
-        println!("Advent of Code 2025");
-        println!("Usage: cargo run -- <day>");
-        println!("Example: cargo run -- 1");
-        println!("\nAvailable days:");
-        println!("  1: Calorie Counting");
+    let cli = match cli::parse_args(env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    let loader = InputLoader::new(cli.input.clone(), cli.puzzles_dir.clone());
+    match cli.command {
+        Command::Help => print_help(),
+        Command::Run { day } => run_one(&cli, &loader, day),
+        Command::All => run_all(&cli, &loader),
+        Command::Verify { day } => run_verify(&cli, &loader, day),
+        Command::Bench { day, part } => run_bench(&cli, &loader, day, part),
+    }
+}
+
+fn print_help() {
+    println!("Advent of Code 2025");
+    println!("{}", cli::USAGE);
+    println!("\nExample: cargo run -- 1");
+    println!("\nAvailable days:");
+    for puzzle in days::ALL {
+        println!("  {}: {}", puzzle.day(), puzzle.title());
+    }
+}
+
+/// Read the day's input and run it, turning a missing file into a report
+fn run(cli: &Cli, loader: &InputLoader, puzzle: &dyn Puzzle) -> DayReport {
+    match loader.read(puzzle.day()) {
+        Ok(raw) => runner::run_day(puzzle, &raw, cli.lenient),
+        Err(err) => DayReport::failed(puzzle, RunError::Input(err.to_string())),
+    }
+}
+
+fn run_one(cli: &Cli, loader: &InputLoader, day: u8) {
+    let Some(puzzle) = days::get(day) else {
+        println!("Day {} not yet implemented", day);
+        return;
+    };
+
+    let report = run(cli, loader, puzzle);
+    if let Some(records) = output::render(cli.format, std::slice::from_ref(&report)) {
+        print!("{}", records);
+        if !report.succeeded() {
+            process::exit(1);
+        }
         return;
     }
+    match &report.parts {
+        Ok(parts) => {
+            println!("Day {}: {}", report.day, report.title);
+            println!("Part 1: {}", parts[0].answer_text());
+            println!("Part 2: {}", parts[1].answer_text());
+            if !report.succeeded() {
+                process::exit(1);
+            }
+        }
+        Err(err) => {
+            eprintln!("Day {}: {}", day, err);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_all(cli: &Cli, loader: &InputLoader) {
+    let plan: Vec<_> = days::ALL
+        .iter()
+        .map(|&puzzle| {
+            let raw = loader
+                .read(puzzle.day())
+                .map_err(|err| RunError::Input(err.to_string()));
+            (puzzle, raw)
+        })
+        .collect();
+    let reports = pool::run_days(&plan, cli.lenient, &cli.pool);
+    match output::render(cli.format, &reports) {
+        Some(records) => print!("{}", records),
+        None => print!("{}", report::render_table(&reports, cli.budget)),
+    }
 
-    let day: u8 = args[1]
-        .parse()
-        .expect("Day must be a number between 1 and 12");
+    if !reports.iter().all(DayReport::succeeded) {
+        process::exit(1);
+    }
+}
 
+/// The requested day, or every day if none was given
+fn select(day: Option<u8>) -> Vec<&'static dyn Puzzle> {
     match day {
-        1 => days::day01::run(),
-        2 => days::day02::run(),
-        3 => days::day03::run(),
-        4 => days::day04::run(),
-        5 => days::day05::run(),
-        6 => days::day06::run(),
-        7 => days::day07::run(),
-        8 => days::day08::run(),
-        9 => days::day09::run(),
-        10 => days::day10::run(),
-        11 => days::day11::run(),
-        12 => days::day12::run(),
-        _ => println!("Day {} not yet implemented", day),
+        Some(day) => match days::get(day) {
+            Some(puzzle) => vec![puzzle],
+            None => {
+                eprintln!("Day {} not yet implemented", day);
+                process::exit(2);
+            }
+        },
+        None => days::ALL.to_vec(),
+    }
+}
+
+/// Check each day against its recorded answers, exiting non-zero on any
+/// mismatch or failure
+fn run_verify(cli: &Cli, loader: &InputLoader, day: Option<u8>) {
+    let mut failures = 0;
+    let mut unrecorded = 0;
+    for puzzle in select(day) {
+        let path = loader.day_dir(puzzle.day()).join(verify::ANSWERS_FILE);
+        let expected = match verify::load_answers(&path) {
+            Ok(Some(expected)) => expected,
+            Ok(None) => {
+                println!("Day {}: {}: no {}", puzzle.day(), puzzle.title(), path.display());
+                unrecorded += 2;
+                continue;
+            }
+            Err(err) => {
+                println!("Day {}: {}: {}", puzzle.day(), puzzle.title(), err);
+                failures += 1;
+                continue;
+            }
+        };
+
+        let report = run(cli, loader, puzzle);
+        match verify::check(&report, &expected) {
+            Ok(checks) => {
+                println!("Day {}: {}", puzzle.day(), puzzle.title());
+                for (idx, check) in checks.iter().enumerate() {
+                    println!("  Part {}: {}", idx + 1, check);
+                    failures += usize::from(check.is_failure());
+                    unrecorded += usize::from(*check == Check::Unrecorded);
+                }
+            }
+            Err(err) => {
+                println!("Day {}: {}: {}", puzzle.day(), puzzle.title(), err);
+                failures += 1;
+            }
+        }
+    }
+
+    println!("\n{} failure(s), {} part(s) without a recorded answer", failures, unrecorded);
+    if failures > 0 {
+        process::exit(1);
+    }
+}
+
+/// Benchmark each selected day, comparing against and optionally updating the
+/// saved baseline
+fn run_bench(cli: &Cli, loader: &InputLoader, day: Option<u8>, part: Option<u8>) {
+    let baseline_path = cli
+        .baseline
+        .clone()
+        .unwrap_or_else(|| loader.puzzles_dir.join(bench::BASELINE_FILE));
+    let baseline = match fs::read_to_string(&baseline_path) {
+        Ok(text) => match bench::parse_baseline(&text) {
+            Ok(baseline) => Some(baseline),
+            Err(err) => {
+                eprintln!("{}: {}", baseline_path.display(), err);
+                process::exit(1);
+            }
+        },
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => {
+            eprintln!("{}: {}", baseline_path.display(), err);
+            process::exit(1);
+        }
+    };
+
+    let mut reports = Vec::new();
+    let mut failed = false;
+    for puzzle in select(day) {
+        let result = loader
+            .read(puzzle.day())
+            .map_err(|err| RunError::Input(err.to_string()))
+            .and_then(|raw| {
+                bench::bench_day(puzzle, &raw, cli.lenient, part, &cli.bench)
+            });
+        match result {
+            Ok(report) => {
+                for (phase, err) in &report.failures {
+                    eprintln!("Day {} {}: {}", puzzle.day(), phase, err);
+                    failed = true;
+                }
+                reports.push(report);
+            }
+            Err(err) => {
+                eprintln!("Day {}: {}", puzzle.day(), err);
+                failed = true;
+            }
+        }
+    }
+    print!("{}", report::render_bench(&reports, baseline.as_ref()));
+
+    if cli.save_baseline {
+        let mut updated = baseline.unwrap_or_else(Baseline::new);
+        for report in &reports {
+            bench::record(&mut updated, report);
+        }
+        if let Err(err) = fs::write(&baseline_path, bench::render_baseline(&updated)) {
+            eprintln!("{}: {}", baseline_path.display(), err);
+            process::exit(1);
+        }
+        println!("\nSaved baseline to {}", baseline_path.display());
+    }
+
+    if failed {
+        process::exit(1);
     }
 }
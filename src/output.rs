@@ -0,0 +1,308 @@
+//! Machine-readable rendering of runner results
+//!
+//! Every day becomes one flat record with the same fields in both formats:
+//!
+//! | field        | meaning                                          |
+//! |--------------|--------------------------------------------------|
+//! | `day`        | day number                                       |
+//! | `title`      | puzzle title                                     |
+//! | `input_hash` | FNV-1a hash of the input as 16 hex digits        |
+//! | `parse_ns`   | parse time in nanoseconds                        |
+//! | `part1`      | part 1 answer (JSON string; `null` if none)      |
+//! | `part1_ns`   | part 1 time in nanoseconds                       |
+//! | `part2`      | part 2 answer                                    |
+//! | `part2_ns`   | part 2 time in nanoseconds                       |
+//! | `error`      | why the day produced no answers, if it failed    |
+//!
+//! JSON output is an array of objects; CSV output has a header row. Missing
+//! values are `null` in JSON and empty in CSV.
+
+use crate::answer::Answer;
+use crate::runner::{DayReport, PartReport};
+use std::str::FromStr;
+
+/// How the runner prints results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Human-readable lines or table
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "unknown format '{}', expected text, json or csv",
+                s
+            )),
+        }
+    }
+}
+
+const FIELDS: [&str; 9] = [
+    "day",
+    "title",
+    "input_hash",
+    "parse_ns",
+    "part1",
+    "part1_ns",
+    "part2",
+    "part2_ns",
+    "error",
+];
+
+/// A record field before it is written out
+enum Value {
+    Number(u128),
+    Text(String),
+    Missing,
+}
+
+fn record(report: &DayReport) -> [Value; 9] {
+    let answer = |part: &PartReport| match &part.answer {
+        Ok(Answer::NotApplicable) | Err(_) => Value::Missing,
+        Ok(answer) => Value::Text(answer.to_string()),
+    };
+    let (parse, parts, error) = match &report.parts {
+        Ok(parts) => {
+            let failures: Vec<String> = parts
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, part)| {
+                    let err = part.answer.as_ref().err()?;
+                    Some(format!("part {}: {}", idx + 1, err))
+                })
+                .collect();
+            (
+                Value::Number(report.parse_time.as_nanos()),
+                [
+                    answer(&parts[0]),
+                    Value::Number(parts[0].elapsed.as_nanos()),
+                    answer(&parts[1]),
+                    Value::Number(parts[1].elapsed.as_nanos()),
+                ],
+                if failures.is_empty() {
+                    Value::Missing
+                } else {
+                    Value::Text(failures.join("; "))
+                },
+            )
+        }
+        Err(err) => (
+            Value::Missing,
+            [
+                Value::Missing,
+                Value::Missing,
+                Value::Missing,
+                Value::Missing,
+            ],
+            Value::Text(err.to_string()),
+        ),
+    };
+    let [part1, part1_ns, part2, part2_ns] = parts;
+    [
+        Value::Number(report.day.into()),
+        Value::Text(report.title.to_string()),
+        report
+            .input_hash
+            .map_or(Value::Missing, |hash| Value::Text(format!("{:016x}", hash))),
+        parse,
+        part1,
+        part1_ns,
+        part2,
+        part2_ns,
+        error,
+    ]
+}
+
+/// Render `reports` as records, or `None` for the human-readable format
+pub fn render(format: Format, reports: &[DayReport]) -> Option<String> {
+    match format {
+        Format::Text => None,
+        Format::Json => Some(render_json(reports)),
+        Format::Csv => Some(render_csv(reports)),
+    }
+}
+
+/// Render `reports` as a JSON array with one object per day
+pub fn render_json(reports: &[DayReport]) -> String {
+    let objects: Vec<String> = reports
+        .iter()
+        .map(|report| {
+            let fields: Vec<String> = FIELDS
+                .iter()
+                .zip(record(report))
+                .map(|(name, value)| {
+                    let value = match value {
+                        Value::Number(n) => n.to_string(),
+                        Value::Text(text) => json_string(&text),
+                        Value::Missing => "null".to_string(),
+                    };
+                    format!("\"{}\": {}", name, value)
+                })
+                .collect();
+            format!("  {{{}}}", fields.join(", "))
+        })
+        .collect();
+    if objects.is_empty() {
+        return "[]\n".to_string();
+    }
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
+
+/// Render `reports` as CSV with a header row
+pub fn render_csv(reports: &[DayReport]) -> String {
+    let mut out = FIELDS.join(",");
+    out.push('\n');
+    for report in reports {
+        let cells: Vec<String> = record(report)
+            .into_iter()
+            .map(|value| match value {
+                Value::Number(n) => n.to_string(),
+                Value::Text(text) => csv_field(&text),
+                Value::Missing => String::new(),
+            })
+            .collect();
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quote and escape `text` as a JSON string
+fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if ch.is_control() => out.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Quote `text` if it contains a separator, quote or line break
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+    use crate::runner::RunError;
+    use std::time::Duration;
+
+    fn solved() -> DayReport {
+        DayReport {
+            day: 12,
+            title: "Christmas Tree Farm",
+            input_hash: Some(0xab),
+            parse_time: Duration::from_nanos(50),
+            parts: Ok([
+                PartReport {
+                    answer: Ok(Answer::Unsigned(2)),
+                    elapsed: Duration::from_nanos(700),
+                },
+                PartReport {
+                    answer: Ok(Answer::NotApplicable),
+                    elapsed: Duration::from_nanos(1),
+                },
+            ]),
+        }
+    }
+
+    fn failed() -> DayReport {
+        DayReport {
+            day: 3,
+            title: "Lobby",
+            input_hash: Some(0),
+            parse_time: Duration::from_nanos(10),
+            parts: Err(RunError::Parse(ParseError::new(
+                2,
+                5,
+                "expected a digit, found 'x'",
+            ))),
+        }
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert_eq!("csv".parse(), Ok(Format::Csv));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json = render_json(&[solved(), failed()]);
+        let lines: Vec<&str> = json.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[1],
+            "  {\"day\": 12, \"title\": \"Christmas Tree Farm\", \"input_hash\": \"00000000000000ab\", \
+             \"parse_ns\": 50, \"part1\": \"2\", \"part1_ns\": 700, \"part2\": null, \"part2_ns\": 1, \
+             \"error\": null},"
+        );
+        assert!(lines[2].ends_with(
+            "\"part2_ns\": null, \"error\": \"parse error: line 2, column 5: expected a digit, found 'x'\"}"
+        ));
+        assert_eq!(render_json(&[]), "[]\n");
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let csv = render_csv(&[solved(), failed()]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "day,title,input_hash,parse_ns,part1,part1_ns,part2,part2_ns,error"
+        );
+        assert_eq!(
+            lines[1],
+            "12,Christmas Tree Farm,00000000000000ab,50,2,700,,1,"
+        );
+        assert_eq!(
+            lines[2],
+            "3,Lobby,0000000000000000,,,,,,\"parse error: line 2, column 5: expected a digit, found 'x'\""
+        );
+    }
+
+    #[test]
+    fn test_part_error() {
+        let mut report = solved();
+        if let Ok(parts) = &mut report.parts {
+            parts[0].answer = Err(RunError::TimedOut(Duration::from_secs(2)));
+        }
+        let csv = render_csv(&[report]);
+        assert_eq!(
+            csv.lines().nth(1).unwrap(),
+            "12,Christmas Tree Farm,00000000000000ab,50,,700,,1,part 1: timed out after 2.00s"
+        );
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
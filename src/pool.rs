@@ -0,0 +1,379 @@
+//! Running many days concurrently on a pool of worker threads
+//!
+//! Each day is split into tasks: parsing, then each part once the parse has
+//! finished, so the parts of a slow day run side by side. Results are
+//! collected back into day order.
+//!
+//! Threads can't be cancelled, so a task that overruns its time limit is
+//! abandoned: it is reported as timed out, a replacement worker takes its
+//! place, and the stuck worker exits once the task finally returns.
+
+use crate::answer::Answer;
+use crate::input;
+use crate::runner::{self, DayReport, PartReport, RunError};
+use crate::solution::{ParsedInput, Puzzle};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How many tasks to run at once and how long each may take
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// Number of worker threads; at least 1
+    pub jobs: usize,
+    /// Limit on each parse or part, if any
+    pub timeout: Option<Duration>,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        PoolConfig {
+            jobs: 1,
+            timeout: None,
+        }
+    }
+}
+
+/// One unit of work for a worker
+enum Task {
+    Parse {
+        puzzle: &'static dyn Puzzle,
+        raw: Arc<str>,
+        lenient: bool,
+    },
+    Part {
+        puzzle: &'static dyn Puzzle,
+        part: usize,
+        input: Arc<ParsedInput>,
+    },
+}
+
+struct Job {
+    id: usize,
+    task: Task,
+    /// Set once the coordinator has given up on this job
+    abandoned: Arc<AtomicBool>,
+}
+
+enum Event {
+    Started(usize, Instant),
+    Parsed(usize, Result<ParsedInput, RunError>, Duration),
+    Solved(usize, Result<Answer, RunError>, Duration),
+}
+
+/// What the coordinator knows about a job it has handed out
+struct Pending {
+    day: usize,
+    /// `None` for the parse task, otherwise the part index
+    part: Option<usize>,
+    started: Option<Instant>,
+    abandoned: Arc<AtomicBool>,
+}
+
+/// Results gathered so far for one day
+#[derive(Default)]
+struct DayState {
+    input_hash: Option<u64>,
+    parse_time: Duration,
+    error: Option<RunError>,
+    parts: [Option<PartReport>; 2],
+}
+
+/// Run every day whose input could be read, returning reports in the order of
+/// `days`
+///
+/// Days whose input is an `Err` are reported as failed without being run.
+pub fn run_days(
+    days: &[(&'static dyn Puzzle, Result<String, RunError>)],
+    lenient: bool,
+    config: &PoolConfig,
+) -> Vec<DayReport> {
+    let (job_tx, job_rx) = mpsc::channel::<Job>();
+    let job_rx = Arc::new(Mutex::new(job_rx));
+    let (event_tx, event_rx) = mpsc::channel::<Event>();
+    for _ in 0..config.jobs.max(1) {
+        spawn_worker(&job_rx, &event_tx);
+    }
+
+    let mut states: Vec<DayState> = Vec::with_capacity(days.len());
+    let mut pending: HashMap<usize, Pending> = HashMap::new();
+    let mut next_id = 0;
+    let mut submit = |pending: &mut HashMap<usize, Pending>, day, part, task| {
+        let abandoned = Arc::new(AtomicBool::new(false));
+        pending.insert(
+            next_id,
+            Pending {
+                day,
+                part,
+                started: None,
+                abandoned: Arc::clone(&abandoned),
+            },
+        );
+        // Workers only stop once the job sender is dropped, so this can't fail
+        let _ = job_tx.send(Job {
+            id: next_id,
+            task,
+            abandoned,
+        });
+        next_id += 1;
+    };
+
+    for (day, (puzzle, raw)) in days.iter().enumerate() {
+        let mut state = DayState::default();
+        match raw {
+            Ok(raw) => {
+                state.input_hash = Some(input::hash(raw));
+                let task = Task::Parse {
+                    puzzle: *puzzle,
+                    raw: Arc::from(raw.as_str()),
+                    lenient,
+                };
+                submit(&mut pending, day, None, task);
+            }
+            Err(err) => state.error = Some(err.clone()),
+        }
+        states.push(state);
+    }
+
+    while !pending.is_empty() {
+        let deadline = config.timeout.and_then(|limit| {
+            pending
+                .values()
+                .filter_map(|job| job.started)
+                .min()
+                .map(|started| started + limit)
+        });
+        let event = match deadline {
+            Some(deadline) => {
+                event_rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            }
+            // We hold a sender for replacement workers, so this never disconnects
+            None => event_rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        match event {
+            Ok(Event::Started(id, at)) => {
+                if let Some(job) = pending.get_mut(&id) {
+                    job.started = Some(at);
+                }
+            }
+            Ok(Event::Parsed(id, result, elapsed)) => {
+                let Some(job) = pending.remove(&id) else {
+                    continue;
+                };
+                let state = &mut states[job.day];
+                state.parse_time = elapsed;
+                match result {
+                    Ok(input) => {
+                        let input = Arc::new(input);
+                        for part in 0..2 {
+                            let task = Task::Part {
+                                puzzle: days[job.day].0,
+                                part,
+                                input: Arc::clone(&input),
+                            };
+                            submit(&mut pending, job.day, Some(part), task);
+                        }
+                    }
+                    Err(err) => state.error = Some(err),
+                }
+            }
+            Ok(Event::Solved(id, answer, elapsed)) => {
+                if let Some(job) = pending.remove(&id) {
+                    states[job.day].parts[job.part.unwrap_or(0)] =
+                        Some(PartReport { answer, elapsed });
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                let limit = config.timeout.unwrap_or_default();
+                let now = Instant::now();
+                let expired: Vec<usize> = pending
+                    .iter()
+                    .filter(|(_, job)| job.started.is_some_and(|started| now >= started + limit))
+                    .map(|(&id, _)| id)
+                    .collect();
+                for id in expired {
+                    let job = pending.remove(&id).unwrap();
+                    job.abandoned.store(true, Ordering::SeqCst);
+                    spawn_worker(&job_rx, &event_tx);
+
+                    let state = &mut states[job.day];
+                    let err = RunError::TimedOut(limit);
+                    match job.part {
+                        Some(part) => {
+                            state.parts[part] = Some(PartReport {
+                                answer: Err(err),
+                                elapsed: limit,
+                            })
+                        }
+                        None => {
+                            state.parse_time = limit;
+                            state.error = Some(err);
+                        }
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                unreachable!("coordinator holds an event sender")
+            }
+        }
+    }
+
+    days.iter()
+        .zip(states)
+        .map(|((puzzle, _), state)| {
+            let parts = match (state.error, state.parts) {
+                (Some(err), _) => Err(err),
+                (None, [Some(part1), Some(part2)]) => Ok([part1, part2]),
+                (None, _) => unreachable!("every part of a parsed day is reported"),
+            };
+            DayReport {
+                day: puzzle.day(),
+                title: puzzle.title(),
+                input_hash: state.input_hash,
+                parse_time: state.parse_time,
+                parts,
+            }
+        })
+        .collect()
+}
+
+fn spawn_worker(jobs: &Arc<Mutex<Receiver<Job>>>, events: &Sender<Event>) {
+    let jobs = Arc::clone(jobs);
+    let events = events.clone();
+    thread::spawn(move || loop {
+        // Holding the lock while waiting is fine: only one idle worker can
+        // take the next job anyway
+        let job = match jobs.lock().map(|jobs| jobs.recv()) {
+            Ok(Ok(job)) => job,
+            _ => return,
+        };
+        if events.send(Event::Started(job.id, Instant::now())).is_err() {
+            return;
+        }
+        let event = run_task(job.id, job.task);
+        // A replacement took over while this job overran, so bow out
+        if events.send(event).is_err() || job.abandoned.load(Ordering::SeqCst) {
+            return;
+        }
+    });
+}
+
+fn run_task(id: usize, task: Task) -> Event {
+    let start = Instant::now();
+    match task {
+        Task::Parse {
+            puzzle,
+            raw,
+            lenient,
+        } => {
+            let result = runner::parse(puzzle, &raw, lenient);
+            Event::Parsed(id, result, start.elapsed())
+        }
+        Task::Part {
+            puzzle,
+            part,
+            input,
+        } => {
+            let answer = runner::solve(puzzle, &input, part as u8 + 1);
+            Event::Solved(id, answer, start.elapsed())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days;
+    use crate::error::ParseError;
+    use crate::solution::Solution;
+
+    /// A day whose part 2 takes far longer than the test timeout
+    struct Slow;
+
+    impl Solution for Slow {
+        const DAY: u8 = 99;
+        const TITLE: &'static str = "Slow";
+        type Input = u64;
+
+        fn parse(input: &str) -> Result<Self::Input, ParseError> {
+            input
+                .trim()
+                .parse()
+                .map_err(|_| ParseError::new(1, 1, "not a number"))
+        }
+
+        fn part1(input: &Self::Input) -> Result<Answer, String> {
+            Ok((*input).into())
+        }
+
+        fn part2(input: &Self::Input) -> Result<Answer, String> {
+            if *input == 0 {
+                panic!("zero");
+            }
+            thread::sleep(Duration::from_millis(500));
+            Ok((*input).into())
+        }
+    }
+
+    const DAY01_EXAMPLE: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82";
+
+    #[test]
+    fn test_results_in_day_order() {
+        let input = |day: u8| match day {
+            1 => Ok(DAY01_EXAMPLE.to_string()),
+            _ => Err(RunError::Input(format!("no input for day {}", day))),
+        };
+        let plan: Vec<_> = days::ALL
+            .iter()
+            .map(|&puzzle| (puzzle, input(puzzle.day())))
+            .collect();
+        let config = PoolConfig {
+            jobs: 4,
+            timeout: None,
+        };
+
+        let reports = run_days(&plan, false, &config);
+        let order: Vec<u8> = reports.iter().map(|report| report.day).collect();
+        assert_eq!(order, (1..=12).collect::<Vec<u8>>());
+
+        let parts = reports[0].parts.as_ref().unwrap();
+        assert_eq!(parts[0].answer, Ok(Answer::Signed(3)));
+        assert_eq!(parts[1].answer, Ok(Answer::Signed(6)));
+        assert!(reports[0].input_hash.is_some());
+        assert!(matches!(reports[1].parts, Err(RunError::Input(_))));
+    }
+
+    #[test]
+    fn test_timeout_and_panic() {
+        let plan: Vec<(&'static dyn Puzzle, _)> = vec![
+            (&Slow, Ok("7".to_string())),
+            (&Slow, Ok("0".to_string())),
+            (&Slow, Ok("x".to_string())),
+        ];
+        let config = PoolConfig {
+            jobs: 2,
+            timeout: Some(Duration::from_millis(50)),
+        };
+
+        let start = Instant::now();
+        let reports = run_days(&plan, false, &config);
+        assert!(start.elapsed() < Duration::from_millis(400));
+
+        let parts = reports[0].parts.as_ref().unwrap();
+        assert_eq!(parts[0].answer, Ok(Answer::Unsigned(7)));
+        assert_eq!(
+            parts[1].answer,
+            Err(RunError::TimedOut(Duration::from_millis(50)))
+        );
+        assert!(!reports[0].succeeded());
+
+        let parts = reports[1].parts.as_ref().unwrap();
+        assert_eq!(parts[1].answer, Err(RunError::Panicked("zero".to_string())));
+
+        assert!(matches!(reports[2].parts, Err(RunError::Parse(_))));
+    }
+}
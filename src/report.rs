@@ -0,0 +1,303 @@
+//! Human-readable rendering of runner results
+
+use crate::bench::{self, Baseline, BenchReport};
+use crate::runner::DayReport;
+use std::time::Duration;
+
+/// Default per-part time budget, from the README's "Solutions < 15 seconds" goal
+pub const DEFAULT_BUDGET: Duration = Duration::from_secs(15);
+
+/// Format a duration with a unit suited to its magnitude
+pub fn format_duration(d: Duration) -> String {
+    let micros = d.as_secs_f64() * 1e6;
+    if micros < 1.0 {
+        format!("{}ns", d.as_nanos())
+    } else if micros < 1_000.0 {
+        format!("{:.0}µs", micros)
+    } else if micros < 1_000_000.0 {
+        format!("{:.2}ms", micros / 1e3)
+    } else {
+        format!("{:.2}s", micros / 1e6)
+    }
+}
+
+/// Render one row per day with parse time, answers, per-part times, totals and a
+/// warning for any part slower than `budget`
+pub fn render_table(reports: &[DayReport], budget: Duration) -> String {
+    let header = ["Day", "Title", "Parse", "Part 1", "Time", "Part 2", "Time", "Budget"];
+    let mut rows: Vec<[String; 8]> = Vec::new();
+    let mut parse_total = Duration::ZERO;
+    let mut part_totals = [Duration::ZERO; 2];
+
+    for report in reports {
+        let row = match &report.parts {
+            Ok(parts) => {
+                let over: Vec<String> = parts
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, part)| part.elapsed > budget)
+                    .map(|(idx, _)| format!("part {}", idx + 1))
+                    .collect();
+                let warning = if over.is_empty() {
+                    String::new()
+                } else {
+                    format!("⚠ {} over {}", over.join(", "), format_duration(budget))
+                };
+                parse_total += report.parse_time;
+                for (total, part) in part_totals.iter_mut().zip(parts.iter()) {
+                    *total += part.elapsed;
+                }
+                [
+                    report.day.to_string(),
+                    report.title.to_string(),
+                    format_duration(report.parse_time),
+                    parts[0].answer_text(),
+                    format_duration(parts[0].elapsed),
+                    parts[1].answer_text(),
+                    format_duration(parts[1].elapsed),
+                    warning,
+                ]
+            }
+            // Errors run to the end of the line and don't widen the columns
+            Err(err) => [
+                report.day.to_string(),
+                report.title.to_string(),
+                err.to_string(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+            ],
+        };
+        rows.push(row);
+    }
+
+    let total_time: Duration = reports.iter().map(DayReport::total_time).sum();
+    rows.push([
+        String::new(),
+        "Total".to_string(),
+        format_duration(parse_total),
+        String::new(),
+        format_duration(part_totals[0]),
+        String::new(),
+        format_duration(part_totals[1]),
+        format_duration(total_time),
+    ]);
+
+    let failed: Vec<bool> = reports
+        .iter()
+        .map(|report| report.parts.is_err())
+        .chain([false])
+        .collect();
+
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for (row, &failed) in rows.iter().zip(&failed) {
+        let measured = if failed { 2 } else { row.len() };
+        for (width, cell) in widths.iter_mut().zip(row.iter()).take(measured) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: &[&str]| -> String {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(idx, (cell, &width))| {
+                // Left-align the title and answers, right-align numbers
+                if matches!(idx, 1 | 3 | 5 | 7) {
+                    format!("{:<width$}", cell, width = width)
+                } else {
+                    format!("{:>width$}", cell, width = width)
+                }
+            })
+            .collect();
+        line.join("  ").trim_end().to_string()
+    };
+
+    let mut out = String::new();
+    out.push_str(&format_row(&header));
+    out.push('\n');
+    let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+    out.push_str(&rule.join("  "));
+    out.push('\n');
+    for (idx, row) in rows.iter().enumerate() {
+        if idx == rows.len() - 1 {
+            out.push_str(&rule.join("  "));
+            out.push('\n');
+        }
+        let cells: Vec<&str> = row.iter().map(String::as_str).collect();
+        if failed[idx] {
+            out.push_str(&format!(
+                "{:>day_width$}  {:<title_width$}  {}",
+                cells[0],
+                cells[1],
+                cells[2],
+                day_width = widths[0],
+                title_width = widths[1],
+            ));
+        } else {
+            out.push_str(&format_row(&cells));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Render benchmark statistics, one row per phase, with the change in median
+/// against `baseline` where it has an entry
+pub fn render_bench(reports: &[BenchReport], baseline: Option<&Baseline>) -> String {
+    let header = ["Day", "Title", "Phase", "Min", "Median", "Mean", "Stddev", "Change"];
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    for report in reports {
+        for (idx, (phase, stats)) in report.phases.iter().enumerate() {
+            let change = baseline
+                .and_then(|baseline| baseline.get(&phase.key(report.day)))
+                .map(|&before| format!("{:+.1}%", bench::change(stats.median, before)))
+                .unwrap_or_default();
+            let (day, title) = if idx == 0 {
+                (report.day.to_string(), report.title.to_string())
+            } else {
+                (String::new(), String::new())
+            };
+            rows.push(vec![
+                day,
+                title,
+                phase.to_string(),
+                format_duration(stats.min),
+                format_duration(stats.median),
+                format_duration(stats.mean),
+                format_duration(stats.stddev),
+                change,
+            ]);
+        }
+    }
+
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: &[&str]| -> String {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(idx, (cell, &width))| {
+                // Left-align the title and phase, right-align numbers
+                if matches!(idx, 1 | 2) {
+                    format!("{:<width$}", cell, width = width)
+                } else {
+                    format!("{:>width$}", cell, width = width)
+                }
+            })
+            .collect();
+        line.join("  ").trim_end().to_string()
+    };
+
+    let mut out = String::new();
+    out.push_str(&format_row(&header));
+    out.push('\n');
+    let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+    out.push_str(&rule.join("  "));
+    out.push('\n');
+    for row in &rows {
+        let cells: Vec<&str> = row.iter().map(String::as_str).collect();
+        out.push_str(&format_row(&cells));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::Answer;
+    use crate::bench::Phase;
+    use crate::error::ParseError;
+    use crate::runner::{PartReport, RunError};
+
+    fn report(day: u8, times_ms: [u64; 2]) -> DayReport {
+        DayReport {
+            day,
+            title: "Test Day",
+            input_hash: None,
+            parse_time: Duration::from_millis(1),
+            parts: Ok(times_ms.map(|ms| PartReport {
+                answer: Ok(Answer::Signed(ms as i64)),
+                elapsed: Duration::from_millis(ms),
+            })),
+        }
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(325)), "325ns");
+        assert_eq!(format_duration(Duration::from_micros(850)), "850µs");
+        assert_eq!(format_duration(Duration::from_micros(12_345)), "12.35ms");
+        assert_eq!(format_duration(Duration::from_millis(1_500)), "1.50s");
+    }
+
+    #[test]
+    fn test_render_table_totals() {
+        let table = render_table(&[report(1, [2, 3]), report(2, [4, 5])], DEFAULT_BUDGET);
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert!(lines[0].starts_with("Day  Title"));
+        assert_eq!(lines.len(), 6);
+        assert!(lines[5].contains("Total"));
+        assert!(lines[5].contains("2.00ms"));
+        assert!(lines[5].contains("6.00ms"));
+        assert!(lines[5].contains("8.00ms"));
+        assert!(lines[5].ends_with("16.00ms"));
+        assert!(lines[2].starts_with("  1  Test Day  1.00ms  2 "));
+        assert!(!table.contains('⚠'));
+    }
+
+    #[test]
+    fn test_render_table_budget_warning() {
+        let table = render_table(&[report(1, [2, 30])], Duration::from_millis(10));
+        assert!(table.lines().nth(2).unwrap().ends_with("⚠ part 2 over 10.00ms"));
+    }
+
+    #[test]
+    fn test_render_table_error_row() {
+        let failed = DayReport {
+            day: 3,
+            title: "Broken",
+            input_hash: None,
+            parse_time: Duration::ZERO,
+            parts: Err(RunError::Parse(ParseError::new(1, 2, "bad"))),
+        };
+        let table = render_table(&[report(1, [2, 3]), failed], DEFAULT_BUDGET);
+        let rule = table.lines().nth(1).unwrap();
+        let row = table.lines().nth(3).unwrap();
+        assert!(row.ends_with("parse error: line 1, column 2: bad"));
+        // The error starts where the Parse column does
+        let parse_column: usize = rule.split("  ").take(2).map(|dashes| dashes.len() + 2).sum();
+        assert_eq!(row.find("parse error"), Some(parse_column));
+    }
+
+    #[test]
+    fn test_render_bench() {
+        let stats = |ms| bench::Stats::from_samples(&[Duration::from_millis(ms)]);
+        let report = BenchReport {
+            day: 4,
+            title: "Test Day",
+            phases: vec![(Phase::Parse, stats(1)), (Phase::Part(1), stats(3))],
+            failures: Vec::new(),
+        };
+        let baseline = Baseline::from([("day04.part1".to_string(), 2_000_000)]);
+
+        let table = render_bench(&[report], Some(&baseline));
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[2].starts_with("  4  Test Day  parse"));
+        assert!(lines[2].ends_with("0ns"));
+        assert!(lines[3].contains("part 1"));
+        assert!(lines[3].ends_with("+50.0%"));
+    }
+}
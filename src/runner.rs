@@ -0,0 +1,244 @@
+//! Executes registered days and records answers and timings
+
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::input;
+use crate::report::format_duration;
+use crate::solution::{ParsedInput, Puzzle};
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+/// Why a day produced no answers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunError {
+    /// The puzzle input could not be read
+    Input(String),
+    /// The puzzle input could not be parsed
+    Parse(ParseError),
+    /// The part found no answer, with the reason
+    Failed(String),
+    /// The step was still running when its time limit ran out
+    TimedOut(Duration),
+    /// The step panicked, with the panic message
+    Panicked(String),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Input(msg) => write!(f, "input error: {}", msg),
+            RunError::Parse(err) => write!(f, "parse error: {}", err),
+            RunError::Failed(msg) => write!(f, "failed: {}", msg),
+            RunError::TimedOut(limit) => write!(f, "timed out after {}", format_duration(*limit)),
+            RunError::Panicked(msg) => write!(f, "panicked: {}", msg),
+        }
+    }
+}
+
+/// The answer to one part and how long it took to compute
+#[derive(Debug, Clone)]
+pub struct PartReport {
+    /// The answer, or why this part alone failed (e.g. it timed out)
+    pub answer: Result<Answer, RunError>,
+    pub elapsed: Duration,
+}
+
+impl PartReport {
+    /// The answer, or the error in its place
+    pub fn answer_text(&self) -> String {
+        match &self.answer {
+            Ok(answer) => answer.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+}
+
+/// The outcome of running both parts of one day
+#[derive(Debug, Clone)]
+pub struct DayReport {
+    pub day: u8,
+    pub title: &'static str,
+    /// [`input::hash`] of the raw input, if it could be read
+    pub input_hash: Option<u64>,
+    pub parse_time: Duration,
+    pub parts: Result<[PartReport; 2], RunError>,
+}
+
+impl DayReport {
+    /// A report for a day that could not be run at all
+    pub fn failed(puzzle: &dyn Puzzle, error: RunError) -> Self {
+        DayReport {
+            day: puzzle.day(),
+            title: puzzle.title(),
+            input_hash: None,
+            parse_time: Duration::ZERO,
+            parts: Err(error),
+        }
+    }
+
+    /// Whether the day was parsed and both parts produced an answer
+    pub fn succeeded(&self) -> bool {
+        self.parts
+            .as_ref()
+            .is_ok_and(|parts| parts.iter().all(|part| part.answer.is_ok()))
+    }
+
+    /// Parse plus both parts
+    pub fn total_time(&self) -> Duration {
+        let parts = match &self.parts {
+            Ok(parts) => parts.iter().map(|part| part.elapsed).sum(),
+            Err(_) => Duration::ZERO,
+        };
+        self.parse_time + parts
+    }
+}
+
+/// Parse `raw` and solve both parts, timing each step
+pub fn run_day(puzzle: &dyn Puzzle, raw: &str, lenient: bool) -> DayReport {
+    let start = Instant::now();
+    let parsed = parse(puzzle, raw, lenient);
+    let parse_time = start.elapsed();
+
+    let parts = parsed.map(|input| [time(|| solve(puzzle, &input, 1)), time(|| solve(puzzle, &input, 2))]);
+
+    DayReport {
+        day: puzzle.day(),
+        title: puzzle.title(),
+        input_hash: Some(input::hash(raw)),
+        parse_time,
+        parts,
+    }
+}
+
+fn time(solve: impl FnOnce() -> Result<Answer, RunError>) -> PartReport {
+    let start = Instant::now();
+    let answer = solve();
+    PartReport {
+        answer,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Parse `raw`, reporting a parse error or a panic in its place
+///
+/// Every way of running a day parses through here, so they all survive a
+/// panicking day the same way.
+pub(crate) fn parse(puzzle: &dyn Puzzle, raw: &str, lenient: bool) -> Result<ParsedInput, RunError> {
+    catch(|| {
+        if lenient {
+            puzzle.parse_lenient(raw)
+        } else {
+            puzzle.parse(raw)
+        }
+    })
+    .and_then(|parsed| parsed.map_err(RunError::Parse))
+}
+
+/// Solve part 1 or 2, reporting a failure or a panic in place of the answer
+pub(crate) fn solve(puzzle: &dyn Puzzle, input: &ParsedInput, part: u8) -> Result<Answer, RunError> {
+    catch(|| match part {
+        1 => puzzle.part1(input),
+        _ => puzzle.part2(input),
+    })
+    .and_then(|answer| answer.map_err(RunError::Failed))
+}
+
+/// Run `f`, turning a panic into an error so one failing step doesn't take
+/// down the whole run
+fn catch<T>(f: impl FnOnce() -> T) -> Result<T, RunError> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map_err(|payload| RunError::Panicked(panic_message(&*payload)))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days;
+    use crate::solution::Solution;
+
+    #[test]
+    fn test_run_day() {
+        let report = run_day(days::get(1).unwrap(), "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82", false);
+        assert_eq!(report.day, 1);
+        assert_eq!(report.title, "Secret Entrance");
+        assert!(report.input_hash.is_some());
+
+        let parts = report.parts.as_ref().unwrap();
+        assert_eq!(parts[0].answer, Ok(Answer::Signed(3)));
+        assert_eq!(parts[1].answer, Ok(Answer::Signed(6)));
+        assert!(report.succeeded());
+        assert!(report.total_time() >= parts[0].elapsed + parts[1].elapsed);
+    }
+
+    #[test]
+    fn test_run_day_parse_error() {
+        let report = run_day(days::get(1).unwrap(), "L68\nbogus", false);
+        assert!(matches!(report.parts, Err(RunError::Parse(ref err)) if err.line == 2));
+
+        assert!(!report.succeeded());
+
+        let report = run_day(days::get(1).unwrap(), "L68\nbogus", true);
+        assert!(report.parts.is_ok());
+    }
+
+    #[test]
+    fn test_run_day_part_fails() {
+        // Day 10 can't reach a joltage target that no button raises
+        let report = run_day(days::get(10).unwrap(), "[..] (0) {1,1}", false);
+        let parts = report.parts.as_ref().unwrap();
+        assert_eq!(parts[0].answer, Ok(Answer::Unsigned(0)));
+        assert_eq!(
+            parts[1].answer,
+            Err(RunError::Failed("machine 1: no solution satisfies the constraints".to_string()))
+        );
+        assert!(!report.succeeded());
+    }
+
+    /// A day that panics on any input of zero
+    struct Fragile;
+
+    impl Solution for Fragile {
+        const DAY: u8 = 99;
+        const TITLE: &'static str = "Fragile";
+        type Input = u64;
+
+        fn parse(input: &str) -> Result<Self::Input, ParseError> {
+            input
+                .trim()
+                .parse()
+                .map_err(|_| ParseError::new(1, 1, "not a number"))
+        }
+
+        fn part1(input: &Self::Input) -> Result<Answer, String> {
+            Ok((*input).into())
+        }
+
+        fn part2(input: &Self::Input) -> Result<Answer, String> {
+            Ok((100 / *input).into())
+        }
+    }
+
+    #[test]
+    fn test_run_day_part_panics() {
+        let report = run_day(&Fragile, "0", false);
+        let parts = report.parts.as_ref().unwrap();
+        assert_eq!(parts[0].answer, Ok(Answer::Unsigned(0)));
+        assert_eq!(
+            parts[1].answer,
+            Err(RunError::Panicked("attempt to divide by zero".to_string()))
+        );
+        assert!(!report.succeeded());
+    }
+}
@@ -0,0 +1,100 @@
+//! The `Solution` trait implemented by every day
+//!
+//! Each day module defines a unit struct (e.g. `Day01`) implementing
+//! [`Solution`]. The blanket [`Puzzle`] impl erases the associated input
+//! type so the registry in `days/mod.rs` can hold every day in one list.
+
+use crate::answer::Answer;
+use crate::error::ParseError;
+use std::any::Any;
+
+/// A single day's puzzle solution
+pub trait Solution {
+    /// Day number (1-25)
+    const DAY: u8;
+
+    /// Puzzle title as shown on adventofcode.com
+    const TITLE: &'static str;
+
+    /// Parsed puzzle input shared by both parts
+    ///
+    /// Days that don't benefit from a parsed model can simply use `String`.
+    type Input: Send + Sync + 'static;
+
+    /// Parse the raw puzzle input, rejecting anything malformed
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    /// Parse the raw puzzle input, skipping malformed entries where the
+    /// day supports it
+    ///
+    /// Defaults to the strict [`Solution::parse`].
+    fn parse_lenient(input: &str) -> Result<Self::Input, ParseError> {
+        Self::parse(input)
+    }
+
+    /// Solve part 1 from the parsed input
+    ///
+    /// An error explains why this input has no answer (e.g. an unsolvable
+    /// machine), and is reported in place of the answer.
+    fn part1(input: &Self::Input) -> Result<Answer, String>;
+
+    /// Solve part 2 from the parsed input, failing like [`Solution::part1`]
+    fn part2(input: &Self::Input) -> Result<Answer, String>;
+}
+
+/// Type-erased parsed input, produced by [`Puzzle::parse`]
+pub type ParsedInput = Box<dyn Any + Send + Sync>;
+
+/// Object-safe view of a [`Solution`], used by the day registry
+pub trait Puzzle: Sync {
+    /// Day number (1-25)
+    fn day(&self) -> u8;
+
+    /// Puzzle title
+    fn title(&self) -> &'static str;
+
+    /// Parse the raw puzzle input into this day's model
+    fn parse(&self, input: &str) -> Result<ParsedInput, ParseError>;
+
+    /// Parse the raw puzzle input, skipping malformed entries where supported
+    fn parse_lenient(&self, input: &str) -> Result<ParsedInput, ParseError>;
+
+    /// Solve part 1 from input produced by [`Puzzle::parse`]
+    fn part1(&self, input: &ParsedInput) -> Result<Answer, String>;
+
+    /// Solve part 2 from input produced by [`Puzzle::parse`]
+    fn part2(&self, input: &ParsedInput) -> Result<Answer, String>;
+}
+
+impl<S: Solution + Sync> Puzzle for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn parse(&self, input: &str) -> Result<ParsedInput, ParseError> {
+        Ok(Box::new(S::parse(input)?))
+    }
+
+    fn parse_lenient(&self, input: &str) -> Result<ParsedInput, ParseError> {
+        Ok(Box::new(S::parse_lenient(input)?))
+    }
+
+    fn part1(&self, input: &ParsedInput) -> Result<Answer, String> {
+        S::part1(downcast::<S>(input))
+    }
+
+    fn part2(&self, input: &ParsedInput) -> Result<Answer, String> {
+        S::part2(downcast::<S>(input))
+    }
+}
+
+/// Recover the concrete input type of a parsed input
+fn downcast<S: Solution>(input: &ParsedInput) -> &S::Input {
+    input
+        .downcast_ref::<S::Input>()
+        .unwrap_or_else(|| panic!("Input was not parsed by day {}", S::DAY))
+}
@@ -0,0 +1,372 @@
+//! Exact cover by Knuth's Algorithm X with dancing links
+//!
+//! Each row of the problem is a set of columns. A solution picks rows so
+//! that every primary column is covered exactly as often as its multiplicity
+//! (once unless set otherwise) and every secondary column at most once, which
+//! makes secondary columns optional. Search always branches on the primary
+//! column with the fewest rows left, and an exhausted search proves that no
+//! cover exists.
+//!
+//! A column with multiplicity `k` picks its rows in list order, so the same
+//! set of rows is never tried as `k!` different sequences; this is what lets
+//! identical pieces share one column instead of needing one column each.
+
+/// An exact cover problem stored as a toroidal doubly-linked sparse matrix
+///
+/// Index 0 is the root, `1..=columns` the column headers and the rest one
+/// node per set entry. Primary headers are linked into a ring with the root;
+/// secondary ones link only to themselves so search never branches on them.
+#[derive(Debug, Clone)]
+pub struct ExactCover {
+    primary: usize,
+    columns: usize,
+    rows: usize,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// Header of each node's column
+    col: Vec<usize>,
+    /// Row id of each node
+    row: Vec<usize>,
+    /// Nodes still linked into each column, indexed by header
+    len: Vec<usize>,
+    /// Covers each column still needs, indexed by header
+    need: Vec<usize>,
+}
+
+impl ExactCover {
+    /// A problem with columns `0..primary` required and the next `secondary`
+    /// optional
+    pub fn new(primary: usize, secondary: usize) -> Self {
+        let columns = primary + secondary;
+        let headers = columns + 1;
+        let mut left: Vec<usize> = (0..headers).collect();
+        let mut right: Vec<usize> = (0..headers).collect();
+        for h in 0..=primary {
+            left[h] = if h == 0 { primary } else { h - 1 };
+            right[h] = if h == primary { 0 } else { h + 1 };
+        }
+        ExactCover {
+            primary,
+            columns,
+            rows: 0,
+            left,
+            right,
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            col: (0..headers).collect(),
+            row: vec![usize::MAX; headers],
+            len: vec![0; headers],
+            need: vec![1; headers],
+        }
+    }
+
+    /// Number of rows added so far
+    pub fn row_count(&self) -> usize {
+        self.rows
+    }
+
+    /// Require primary column `column` to be covered exactly `count` times
+    ///
+    /// A count of 0 rules out every row that uses the column.
+    pub fn set_multiplicity(&mut self, column: usize, count: usize) {
+        assert!(
+            column < self.primary,
+            "only primary columns have a multiplicity"
+        );
+        self.need[column + 1] = count;
+    }
+
+    /// Add a row covering the given distinct columns, returning its id
+    ///
+    /// Ids count up from 0 in the order rows are added.
+    pub fn add_row(&mut self, columns: &[usize]) -> usize {
+        let id = self.rows;
+        self.rows += 1;
+        let mut first: Option<usize> = None;
+        for &column in columns {
+            assert!(
+                column < self.columns,
+                "column {} out of range for {} columns",
+                column,
+                self.columns
+            );
+            let header = column + 1;
+            let node = self.col.len();
+            self.col.push(header);
+            self.row.push(id);
+
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            let above = self.up[header];
+            self.down[above] = node;
+            self.up[header] = node;
+            self.len[header] += 1;
+
+            match first {
+                None => {
+                    self.left.push(node);
+                    self.right.push(node);
+                    first = Some(node);
+                }
+                Some(first) => {
+                    let last = self.left[first];
+                    self.left.push(last);
+                    self.right.push(first);
+                    self.right[last] = node;
+                    self.left[first] = node;
+                }
+            }
+        }
+        id
+    }
+
+    /// Ids of rows forming a cover, or `None` once search has ruled every
+    /// combination out
+    ///
+    /// The problem is left as it was, so it can be solved again.
+    pub fn solve(&mut self) -> Option<Vec<usize>> {
+        // Columns needing no covers are satisfied before search starts
+        let satisfied: Vec<usize> = (1..=self.primary).filter(|&h| self.need[h] == 0).collect();
+        for &h in &satisfied {
+            self.cover(h);
+        }
+
+        let mut chosen = Vec::new();
+        let found = self.search(&mut chosen);
+
+        for &h in satisfied.iter().rev() {
+            self.uncover(h);
+        }
+        found.then_some(chosen)
+    }
+
+    fn search(&mut self, chosen: &mut Vec<usize>) -> bool {
+        if self.right[0] == 0 {
+            return true;
+        }
+
+        // Branch on the column with the fewest rows left
+        let mut best = 0;
+        let mut h = self.right[0];
+        while h != 0 {
+            if self.len[h] < self.need[h] {
+                return false;
+            }
+            if best == 0 || self.len[h] < self.len[best] {
+                best = h;
+            }
+            h = self.right[h];
+        }
+
+        // Try each row as the first one chosen for `best`, dropping it for
+        // the rest of the siblings once it has been tried
+        let mut tried = Vec::new();
+        let mut found = false;
+        while self.down[best] != best {
+            let node = self.down[best];
+            self.remove_row(node);
+            tried.push(node);
+
+            self.select(node);
+            chosen.push(self.row[node]);
+            found = self.search(chosen);
+            if !found {
+                chosen.pop();
+            }
+            self.deselect(node);
+            if found {
+                break;
+            }
+        }
+        for &node in tried.iter().rev() {
+            self.restore_row(node);
+        }
+        found
+    }
+
+    /// Count one cover of each column of the removed row holding `node`,
+    /// covering those that need no more
+    fn select(&mut self, node: usize) {
+        let mut j = node;
+        loop {
+            let h = self.col[j];
+            self.need[h] -= 1;
+            if self.need[h] == 0 {
+                self.cover(h);
+            }
+            j = self.right[j];
+            if j == node {
+                break;
+            }
+        }
+    }
+
+    /// Undo `select` in reverse order
+    fn deselect(&mut self, node: usize) {
+        let mut j = self.left[node];
+        loop {
+            let h = self.col[j];
+            if self.need[h] == 0 {
+                self.uncover(h);
+            }
+            self.need[h] += 1;
+            if j == node {
+                break;
+            }
+            j = self.left[j];
+        }
+    }
+
+    /// Unlink every node of a row from its column
+    fn remove_row(&mut self, node: usize) {
+        let mut j = node;
+        loop {
+            self.unlink_vertical(j);
+            j = self.right[j];
+            if j == node {
+                break;
+            }
+        }
+    }
+
+    fn restore_row(&mut self, node: usize) {
+        let mut j = self.left[node];
+        loop {
+            self.relink_vertical(j);
+            if j == node {
+                break;
+            }
+            j = self.left[j];
+        }
+    }
+
+    /// Take a column out of the search along with every row that uses it
+    fn cover(&mut self, h: usize) {
+        // Secondary headers link to themselves, making this a no-op for them
+        let (l, r) = (self.left[h], self.right[h]);
+        self.right[l] = r;
+        self.left[r] = l;
+
+        let mut i = self.down[h];
+        while i != h {
+            let mut j = self.right[i];
+            while j != i {
+                self.unlink_vertical(j);
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, h: usize) {
+        let mut i = self.up[h];
+        while i != h {
+            let mut j = self.left[i];
+            while j != i {
+                self.relink_vertical(j);
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+
+        let (l, r) = (self.left[h], self.right[h]);
+        self.right[l] = h;
+        self.left[r] = h;
+    }
+
+    fn unlink_vertical(&mut self, j: usize) {
+        let (u, d) = (self.up[j], self.down[j]);
+        self.down[u] = d;
+        self.up[d] = u;
+        self.len[self.col[j]] -= 1;
+    }
+
+    fn relink_vertical(&mut self, j: usize) {
+        let (u, d) = (self.up[j], self.down[j]);
+        self.down[u] = j;
+        self.up[d] = j;
+        self.len[self.col[j]] += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Whether `rows` picked from `sets` cover each column as required
+    fn covers(sets: &[&[usize]], rows: &[usize], needs: &[usize], optional: usize) -> bool {
+        let mut counts = vec![0; needs.len() + optional];
+        for &row in rows {
+            for &column in sets[row] {
+                counts[column] += 1;
+            }
+        }
+        counts[..needs.len()] == *needs && counts[needs.len()..].iter().all(|&c| c <= 1)
+    }
+
+    #[test]
+    fn test_knuth_example() {
+        // The example from "Dancing Links", whose only cover is rows 0, 3, 4
+        let sets: [&[usize]; 6] = [
+            &[2, 4, 5],
+            &[0, 3, 6],
+            &[1, 2, 5],
+            &[0, 3],
+            &[1, 6],
+            &[3, 4, 6],
+        ];
+        let mut problem = ExactCover::new(7, 0);
+        for set in sets {
+            problem.add_row(set);
+        }
+        let mut rows = problem.solve().unwrap();
+        rows.sort_unstable();
+        assert_eq!(rows, [0, 3, 4]);
+
+        // Solving leaves the links as they were
+        assert_eq!(problem.solve().map(|rows| rows.len()), Some(3));
+    }
+
+    #[test]
+    fn test_no_cover() {
+        let mut problem = ExactCover::new(3, 0);
+        problem.add_row(&[0, 1]);
+        problem.add_row(&[1, 2]);
+        assert_eq!(problem.solve(), None);
+        assert_eq!(ExactCover::new(0, 2).solve(), Some(vec![]));
+    }
+
+    #[test]
+    fn test_secondary_columns() {
+        // Column 2 is optional but may be used at most once
+        let sets: [&[usize]; 3] = [&[0, 2], &[1, 2], &[1]];
+        let mut problem = ExactCover::new(2, 1);
+        for set in sets {
+            problem.add_row(set);
+        }
+        let rows = problem.solve().unwrap();
+        assert!(covers(&sets, &rows, &[1, 1], 1));
+        assert!(rows.contains(&2), "rows 0 and 1 clash on column 2");
+    }
+
+    #[test]
+    fn test_multiplicity() {
+        // Column 0 needs two of the three rows, which must not clash on 1-3
+        let sets: [&[usize]; 3] = [&[0, 1, 2], &[0, 2, 3], &[0, 3]];
+        let mut problem = ExactCover::new(1, 3);
+        problem.set_multiplicity(0, 2);
+        for set in sets {
+            problem.add_row(set);
+        }
+        let rows = problem.solve().unwrap();
+        assert!(covers(&sets, &rows, &[2], 3));
+
+        problem.set_multiplicity(0, 3);
+        assert_eq!(problem.solve(), None);
+
+        problem.set_multiplicity(0, 0);
+        assert_eq!(problem.solve(), Some(vec![]));
+    }
+}
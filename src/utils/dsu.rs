@@ -0,0 +1,247 @@
+//! Disjoint-set forest (union-find) over the elements `0..n`
+//!
+//! Unions are by size and `find` compresses paths iteratively, so long chains
+//! can't overflow the stack. A forest made with `UnionFind::with_rollback`
+//! keeps a history of its unions instead of compressing paths, so they can be
+//! undone in reverse order, e.g. for offline dynamic connectivity.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+    /// `(child, root)` for every successful union, in rollback mode
+    history: Option<Vec<(usize, usize)>>,
+}
+
+impl UnionFind {
+    /// `n` singleton sets
+    pub fn new(n: usize) -> Self {
+        UnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+            history: None,
+        }
+    }
+
+    /// `n` singleton sets whose unions can be rolled back
+    pub fn with_rollback(n: usize) -> Self {
+        UnionFind {
+            history: Some(Vec::new()),
+            ..UnionFind::new(n)
+        }
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Representative of the set holding `x`
+    pub fn find(&mut self, x: usize) -> usize {
+        let root = self.root(x);
+        if self.history.is_none() {
+            let mut node = x;
+            while node != root {
+                node = std::mem::replace(&mut self.parent[node], root);
+            }
+        }
+        root
+    }
+
+    /// Representative of the set holding `x`, without compressing the path
+    pub fn root(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Merge the sets holding `x` and `y`, returning whether they were apart
+    pub fn union(&mut self, x: usize, y: usize) -> bool {
+        let (mut root_x, mut root_y) = (self.find(x), self.find(y));
+        if root_x == root_y {
+            return false;
+        }
+        if self.size[root_x] < self.size[root_y] {
+            std::mem::swap(&mut root_x, &mut root_y);
+        }
+        self.parent[root_y] = root_x;
+        self.size[root_x] += self.size[root_y];
+        self.components -= 1;
+        if let Some(history) = &mut self.history {
+            history.push((root_y, root_x));
+        }
+        true
+    }
+
+    pub fn connected(&mut self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    /// Number of elements in the set holding `x`
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Number of disjoint sets
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Every set with its elements in ascending order, ordered by their
+    /// smallest element
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut index: HashMap<usize, usize> = HashMap::new();
+        let mut components: Vec<Vec<usize>> = Vec::with_capacity(self.components);
+        for x in 0..self.len() {
+            let root = self.find(x);
+            let idx = *index.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[idx].push(x);
+        }
+        components
+    }
+
+    /// Size of every set, largest first
+    pub fn component_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = (0..self.len())
+            .filter(|&x| self.parent[x] == x)
+            .map(|root| self.size[root])
+            .collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    /// A marker for the current state to pass to `rollback`
+    ///
+    /// Panics unless the forest was made with `with_rollback`.
+    pub fn snapshot(&self) -> usize {
+        self.history().len()
+    }
+
+    /// Undo every union made since `snapshot` was taken
+    pub fn rollback(&mut self, snapshot: usize) {
+        while self.history().len() > snapshot {
+            self.undo();
+        }
+    }
+
+    /// Undo the most recent successful union, returning whether there was one
+    ///
+    /// Panics unless the forest was made with `with_rollback`.
+    pub fn undo(&mut self) -> bool {
+        let Some((child, root)) = self.history_mut().pop() else {
+            return false;
+        };
+        self.parent[child] = child;
+        self.size[root] -= self.size[child];
+        self.components += 1;
+        true
+    }
+
+    fn history(&self) -> &Vec<(usize, usize)> {
+        self.history
+            .as_ref()
+            .expect("rollback requires UnionFind::with_rollback")
+    }
+
+    fn history_mut(&mut self) -> &mut Vec<(usize, usize)> {
+        self.history
+            .as_mut()
+            .expect("rollback requires UnionFind::with_rollback")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_and_find() {
+        let mut uf = UnionFind::new(5);
+        assert_eq!(uf.component_count(), 5);
+
+        assert!(uf.union(0, 1));
+        assert!(uf.union(2, 3));
+        assert!(uf.connected(0, 1));
+        assert!(!uf.connected(1, 2));
+
+        assert!(uf.union(0, 2));
+        assert_eq!(uf.find(0), uf.find(3));
+        assert!(!uf.union(1, 3), "already connected");
+
+        assert_eq!(uf.component_count(), 2);
+        assert_eq!(uf.size_of(3), 4);
+        assert_eq!(uf.component_sizes(), [4, 1]);
+        assert_eq!(uf.components(), [vec![0, 1, 2, 3], vec![4]]);
+    }
+
+    /// One set of `n` elements where each element's parent is the next
+    /// one, the degenerate shape union by size never builds
+    fn chain(n: usize) -> UnionFind {
+        let mut uf = UnionFind::new(n);
+        for x in 1..n {
+            uf.parent[x - 1] = x;
+        }
+        uf.size[n - 1] = n;
+        uf.components = 1;
+        uf
+    }
+
+    #[test]
+    fn test_long_chain() {
+        let n = 1_000_000;
+        let mut uf = chain(n);
+        assert_eq!(uf.root(0), n - 1);
+        assert_eq!(uf.find(0), n - 1);
+        // The whole path now points straight at the root
+        assert!((0..n).all(|x| uf.parent[x] == n - 1));
+        assert_eq!(uf.size_of(n / 2), n);
+
+        let mut uf = UnionFind::new(n);
+        for x in 1..n {
+            uf.union(x - 1, x);
+        }
+        assert_eq!(uf.component_count(), 1);
+        assert!(uf.connected(0, n - 1));
+    }
+
+    #[test]
+    fn test_rollback() {
+        let mut uf = UnionFind::with_rollback(4);
+        uf.union(0, 1);
+        let snapshot = uf.snapshot();
+
+        uf.union(2, 3);
+        uf.union(1, 1);
+        uf.union(0, 3);
+        assert_eq!(uf.component_count(), 1);
+
+        uf.rollback(snapshot);
+        assert_eq!(uf.component_count(), 3);
+        assert!(uf.connected(0, 1));
+        assert!(!uf.connected(2, 3));
+        assert_eq!(uf.component_sizes(), [2, 1, 1]);
+
+        assert!(uf.undo());
+        assert!(!uf.undo());
+        assert_eq!(uf.components(), [vec![0], vec![1], vec![2], vec![3]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "with_rollback")]
+    fn test_rollback_needs_history() {
+        UnionFind::new(2).undo();
+    }
+}
@@ -0,0 +1,499 @@
+//! Integer points, directions and bounding boxes
+//!
+//! Points use screen coordinates: `x` grows to the right and `y` grows
+//! downwards, so `Direction::Up` is `(0, -1)`. This matches `Grid`, where a
+//! `(row, col)` position corresponds to the point `(col, row)`.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Signed integer types that points can be built from
+pub trait Coord:
+    Copy
+    + Ord
+    + Default
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+    + From<i8>
+{
+}
+
+impl<T> Coord for T where
+    T: Copy
+        + Ord
+        + Default
+        + fmt::Debug
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + Neg<Output = T>
+        + From<i8>
+{
+}
+
+fn abs_diff<T: Coord>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+/// A point on the plane
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Point2<T = i64> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Coord> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+
+    /// Sum of the distances along each axis
+    pub fn manhattan(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    /// Largest distance along any one axis, i.e. king moves on a grid
+    pub fn chebyshev(self, other: Self) -> T {
+        abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+    }
+
+    /// Square of the straight-line distance
+    pub fn distance_squared(self, other: Self) -> T {
+        let (dx, dy) = (self.x - other.x, self.y - other.y);
+        dx * dx + dy * dy
+    }
+
+    /// Rotate a quarter turn clockwise about the origin
+    pub fn rotate_cw(self) -> Self {
+        Point2::new(-self.y, self.x)
+    }
+
+    /// Rotate a quarter turn counter-clockwise about the origin
+    pub fn rotate_ccw(self) -> Self {
+        Point2::new(self.y, -self.x)
+    }
+
+    /// Mirror across the vertical axis
+    pub fn flip_horizontal(self) -> Self {
+        Point2::new(-self.x, self.y)
+    }
+
+    /// The neighbouring point in `dir`
+    pub fn step(self, dir: impl Into<Direction8>) -> Self {
+        self + dir.into().offset()
+    }
+
+    /// Smallest coordinates of both points on each axis
+    pub fn min(self, other: Self) -> Self {
+        Point2::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    /// Largest coordinates of both points on each axis
+    pub fn max(self, other: Self) -> Self {
+        Point2::new(self.x.max(other.x), self.y.max(other.y))
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(p: Point2<T>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// A point in space
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Point3<T = i64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Coord> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+
+    /// Sum of the distances along each axis
+    pub fn manhattan(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y) + abs_diff(self.z, other.z)
+    }
+
+    /// Largest distance along any one axis
+    pub fn chebyshev(self, other: Self) -> T {
+        abs_diff(self.x, other.x)
+            .max(abs_diff(self.y, other.y))
+            .max(abs_diff(self.z, other.z))
+    }
+
+    /// Square of the straight-line distance
+    pub fn distance_squared(self, other: Self) -> T {
+        let (dx, dy, dz) = (self.x - other.x, self.y - other.y, self.z - other.z);
+        dx * dx + dy * dy + dz * dz
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+/// Component-wise arithmetic shared by both point types
+macro_rules! impl_point_ops {
+    ($point:ident { $($field:ident),+ }) => {
+        impl<T: Coord> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                $point { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl<T: Coord> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                $point { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl<T: Coord> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $point { $($field: -self.$field),+ }
+            }
+        }
+
+        /// Scale by a constant
+        impl<T: Coord> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, k: T) -> Self {
+                $point { $($field: self.$field * k),+ }
+            }
+        }
+
+        impl<T: Coord> AddAssign for $point<T> {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl<T: Coord> SubAssign for $point<T> {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+    };
+}
+
+impl_point_ops!(Point2 { x, y });
+impl_point_ops!(Point3 { x, y, z });
+
+/// One of the four orthogonal directions, listed clockwise from up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    fn nth(n: usize) -> Self {
+        Self::ALL[n % 4]
+    }
+
+    /// A quarter turn clockwise
+    pub fn turn_right(self) -> Self {
+        Self::nth(self as usize + 1)
+    }
+
+    /// A quarter turn counter-clockwise
+    pub fn turn_left(self) -> Self {
+        Self::nth(self as usize + 3)
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::nth(self as usize + 2)
+    }
+
+    /// The `(row, col)` change of one step, as used by `Grid::step`
+    pub fn delta(self) -> (isize, isize) {
+        Direction8::from(self).delta()
+    }
+
+    /// One step as a point
+    pub fn offset<T: Coord>(self) -> Point2<T> {
+        Direction8::from(self).offset()
+    }
+}
+
+/// One of the eight compass directions, listed clockwise from up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    pub const ALL: [Direction8; 8] = [
+        Direction8::Up,
+        Direction8::UpRight,
+        Direction8::Right,
+        Direction8::DownRight,
+        Direction8::Down,
+        Direction8::DownLeft,
+        Direction8::Left,
+        Direction8::UpLeft,
+    ];
+
+    fn nth(n: usize) -> Self {
+        Self::ALL[n % 8]
+    }
+
+    /// An eighth of a turn clockwise
+    pub fn rotate_cw(self) -> Self {
+        Self::nth(self as usize + 1)
+    }
+
+    /// An eighth of a turn counter-clockwise
+    pub fn rotate_ccw(self) -> Self {
+        Self::nth(self as usize + 7)
+    }
+
+    /// A quarter turn clockwise
+    pub fn turn_right(self) -> Self {
+        Self::nth(self as usize + 2)
+    }
+
+    /// A quarter turn counter-clockwise
+    pub fn turn_left(self) -> Self {
+        Self::nth(self as usize + 6)
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::nth(self as usize + 4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+
+    /// The `(row, col)` change of one step, as used by `Grid::step`
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Direction8::Up => (-1, 0),
+            Direction8::UpRight => (-1, 1),
+            Direction8::Right => (0, 1),
+            Direction8::DownRight => (1, 1),
+            Direction8::Down => (1, 0),
+            Direction8::DownLeft => (1, -1),
+            Direction8::Left => (0, -1),
+            Direction8::UpLeft => (-1, -1),
+        }
+    }
+
+    /// One step as a point
+    pub fn offset<T: Coord>(self) -> Point2<T> {
+        let (dr, dc) = self.delta();
+        Point2::new(T::from(dc as i8), T::from(dr as i8))
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(dir: Direction) -> Self {
+        Direction8::nth(dir as usize * 2)
+    }
+}
+
+/// The smallest axis-aligned rectangle holding a set of points, edges included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounds2<T = i64> {
+    pub min: Point2<T>,
+    pub max: Point2<T>,
+}
+
+impl<T: Coord> Bounds2<T> {
+    /// The rectangle with `a` and `b` as opposite corners
+    pub fn new(a: Point2<T>, b: Point2<T>) -> Self {
+        Bounds2 {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    /// Bounds of every point in `points`, or `None` if there are none
+    pub fn from_points(points: impl IntoIterator<Item = Point2<T>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = Bounds2::new(first, first);
+        for point in points {
+            bounds.include(point);
+        }
+        Some(bounds)
+    }
+
+    /// Grow to cover `point`
+    pub fn include(&mut self, point: Point2<T>) {
+        self.min = self.min.min(point);
+        self.max = self.max.max(point);
+    }
+
+    pub fn contains(&self, point: Point2<T>) -> bool {
+        self.min.x <= point.x
+            && point.x <= self.max.x
+            && self.min.y <= point.y
+            && point.y <= self.max.y
+    }
+
+    /// Whether `other` lies entirely inside these bounds
+    pub fn contains_bounds(&self, other: &Self) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    /// Number of columns covered
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::from(1)
+    }
+
+    /// Number of rows covered
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::from(1)
+    }
+
+    /// Number of integer points inside
+    pub fn area(&self) -> T {
+        self.width() * self.height()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_arithmetic() {
+        let a = Point2::new(3, -1);
+        let b = Point2::new(1, 4);
+        assert_eq!(a + b, Point2::new(4, 3));
+        assert_eq!(a - b, Point2::new(2, -5));
+        assert_eq!(-a, Point2::new(-3, 1));
+        assert_eq!(a * 2, Point2::new(6, -2));
+
+        let mut c = Point3::new(1, 2, 3);
+        c += Point3::new(1, 1, 1);
+        c -= Point3::new(0, 0, 5);
+        assert_eq!(c, Point3::new(2, 3, -1));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point2::new(0i32, 0);
+        let b = Point2::new(3, -4);
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(a.distance_squared(b), 25);
+
+        let p = Point3::new(1i64, 2, 3);
+        let q = Point3::new(4, 0, 3);
+        assert_eq!(p.manhattan(q), 5);
+        assert_eq!(p.chebyshev(q), 3);
+        assert_eq!(p.distance_squared(q), 13);
+    }
+
+    #[test]
+    fn test_rotations() {
+        let p = Point2::new(2, 1);
+        assert_eq!(p.rotate_cw(), Point2::new(-1, 2));
+        assert_eq!(p.rotate_cw().rotate_ccw(), p);
+        assert_eq!(p.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), p);
+        assert_eq!(p.flip_horizontal(), Point2::new(-2, 1));
+    }
+
+    #[test]
+    fn test_direction_turns() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+
+        assert_eq!(Direction8::UpLeft.rotate_cw(), Direction8::Up);
+        assert_eq!(Direction8::Up.rotate_ccw(), Direction8::UpLeft);
+        assert_eq!(Direction8::UpRight.turn_right(), Direction8::DownRight);
+        assert_eq!(Direction8::DownLeft.reverse(), Direction8::UpRight);
+        assert!(Direction8::DownLeft.is_diagonal());
+        assert!(!Direction8::from(Direction::Down).is_diagonal());
+    }
+
+    #[test]
+    fn test_direction_offsets() {
+        assert_eq!(Direction::Up.delta(), (-1, 0));
+        assert_eq!(Direction::Up.offset::<i32>(), Point2::new(0, -1));
+        assert_eq!(
+            Point2::new(5, 5).step(Direction8::DownLeft),
+            Point2::new(4, 6)
+        );
+
+        // Opposite directions cancel out
+        for dir in Direction8::ALL {
+            assert_eq!(
+                dir.offset::<i64>() + dir.reverse().offset(),
+                Point2::default()
+            );
+        }
+    }
+
+    #[test]
+    fn test_bounds() {
+        let bounds = Bounds2::new(Point2::new(9, 7), Point2::new(2, 5));
+        assert_eq!(bounds.min, Point2::new(2, 5));
+        assert_eq!((bounds.width(), bounds.height(), bounds.area()), (8, 3, 24));
+        assert!(bounds.contains(Point2::new(9, 5)));
+        assert!(!bounds.contains(Point2::new(1, 6)));
+
+        let all = Bounds2::from_points([(1, 1), (4, -2), (0, 3)].map(Point2::from)).unwrap();
+        assert_eq!(all, Bounds2::new(Point2::new(0, -2), Point2::new(4, 3)));
+        assert!(all.contains_bounds(&Bounds2::new(Point2::new(1, 1), Point2::new(4, 3))));
+        assert!(!all.contains_bounds(&bounds));
+        assert_eq!(Bounds2::<i64>::from_points([]), None);
+    }
+}
@@ -0,0 +1,428 @@
+//! Linear algebra over GF(2), the field of bits under XOR and AND
+//!
+//! Rows are packed 64 columns to a word, so eliminating an `m x n` matrix
+//! costs `O(m * m * n / 64)`. Solving `A x = b` finds one solution plus a
+//! basis of the nullspace; every solution is that one XOR some combination
+//! of the basis, so the lightest is found by walking only those `2^nullity`
+//! combinations rather than all `2^n` vectors.
+
+use std::fmt;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A fixed-length vector of bits
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVec {
+    /// `len` zero bits
+    pub fn zeros(len: usize) -> Self {
+        BitVec {
+            words: vec![0; len.div_ceil(WORD_BITS)],
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Panics if `idx` is out of range
+    pub fn get(&self, idx: usize) -> bool {
+        assert!(
+            idx < self.len,
+            "bit {} out of range for {} bits",
+            idx,
+            self.len
+        );
+        self.words[idx / WORD_BITS] >> (idx % WORD_BITS) & 1 == 1
+    }
+
+    /// Panics if `idx` is out of range
+    pub fn set(&mut self, idx: usize, value: bool) {
+        if value != self.get(idx) {
+            self.toggle(idx);
+        }
+    }
+
+    /// Panics if `idx` is out of range
+    pub fn toggle(&mut self, idx: usize) {
+        assert!(
+            idx < self.len,
+            "bit {} out of range for {} bits",
+            idx,
+            self.len
+        );
+        self.words[idx / WORD_BITS] ^= 1 << (idx % WORD_BITS);
+    }
+
+    /// Add `other` to this vector, i.e. XOR them together
+    ///
+    /// Panics if the lengths differ.
+    pub fn xor_assign(&mut self, other: &BitVec) {
+        assert_eq!(self.len, other.len, "bit vectors differ in length");
+        for (word, &other) in self.words.iter_mut().zip(&other.words) {
+            *word ^= other;
+        }
+    }
+
+    /// Number of set bits, i.e. the Hamming weight
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Indices of the set bits in ascending order
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(idx, &word)| {
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                (rest != 0).then(|| {
+                    let bit = rest.trailing_zeros() as usize;
+                    rest &= rest - 1;
+                    idx * WORD_BITS + bit
+                })
+            })
+        })
+    }
+}
+
+impl FromIterator<bool> for BitVec {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let bits: Vec<bool> = iter.into_iter().collect();
+        let mut vec = BitVec::zeros(bits.len());
+        for (idx, bit) in bits.into_iter().enumerate() {
+            if bit {
+                vec.toggle(idx);
+            }
+        }
+        vec
+    }
+}
+
+impl fmt::Display for BitVec {
+    /// Bits in index order as `0`s and `1`s
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for idx in 0..self.len {
+            f.write_str(if self.get(idx) { "1" } else { "0" })?;
+        }
+        Ok(())
+    }
+}
+
+/// A dense matrix over GF(2), stored row by row
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Gf2Matrix {
+    rows: Vec<BitVec>,
+    cols: usize,
+}
+
+impl Gf2Matrix {
+    /// A `rows x cols` matrix of zeros
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Gf2Matrix {
+            rows: vec![BitVec::zeros(cols); rows],
+            cols,
+        }
+    }
+
+    /// A matrix with the given rows
+    ///
+    /// Panics if the rows differ in length.
+    pub fn from_rows(rows: Vec<BitVec>) -> Self {
+        let cols = rows.first().map_or(0, BitVec::len);
+        assert!(
+            rows.iter().all(|row| row.len() == cols),
+            "matrix rows differ in length"
+        );
+        Gf2Matrix { rows, cols }
+    }
+
+    /// A matrix with the given columns, each `rows` bits long
+    ///
+    /// Panics if a column has a different length.
+    pub fn from_columns(rows: usize, columns: &[BitVec]) -> Self {
+        let mut matrix = Gf2Matrix::zeros(rows, columns.len());
+        for (col, column) in columns.iter().enumerate() {
+            assert_eq!(column.len(), rows, "matrix columns differ in length");
+            for row in column.ones() {
+                matrix.rows[row].toggle(col);
+            }
+        }
+        matrix
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn col_count(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        self.rows[row].get(col)
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        self.rows[row].set(col, value);
+    }
+
+    pub fn row(&self, row: usize) -> &BitVec {
+        &self.rows[row]
+    }
+
+    /// `self * x`
+    ///
+    /// Panics unless `x` has one bit per column.
+    pub fn mul_vec(&self, x: &BitVec) -> BitVec {
+        assert_eq!(x.len(), self.cols, "vector length must match the columns");
+        self.rows
+            .iter()
+            .map(|row| {
+                let dot: u32 = row
+                    .words
+                    .iter()
+                    .zip(&x.words)
+                    .map(|(a, b)| (a & b).count_ones())
+                    .sum();
+                dot % 2 == 1
+            })
+            .collect()
+    }
+
+    /// Number of linearly independent rows
+    pub fn rank(&self) -> usize {
+        self.echelon(None).pivots.len()
+    }
+
+    /// A basis of the vectors `x` with `self * x == 0`
+    ///
+    /// There is one basis vector per free column, so `cols - rank` in all.
+    pub fn nullspace(&self) -> Vec<BitVec> {
+        self.echelon(None).nullspace()
+    }
+
+    /// Some `x` with `self * x == b`, if there is one
+    ///
+    /// Free variables are left at zero. Panics unless `b` has one bit per row.
+    pub fn solve(&self, b: &BitVec) -> Option<BitVec> {
+        self.echelon(Some(b)).particular()
+    }
+
+    /// A solution of `self * x == b` with the fewest set bits, if there is one
+    ///
+    /// Walks every solution in Gray code order, so one XOR moves to the next;
+    /// the cost is exponential in the nullity. Ties go to the first solution
+    /// found. Panics unless `b` has one bit per row.
+    pub fn min_weight_solution(&self, b: &BitVec) -> Option<BitVec> {
+        let echelon = self.echelon(Some(b));
+        let mut x = echelon.particular()?;
+        let basis = echelon.nullspace();
+        assert!(
+            basis.len() < WORD_BITS,
+            "nullity {} is too large to enumerate",
+            basis.len()
+        );
+
+        let mut best = x.clone();
+        let mut best_weight = x.count_ones();
+        for step in 1_u64..1 << basis.len() {
+            // The Gray code for `step` differs from the last in this bit
+            x.xor_assign(&basis[step.trailing_zeros() as usize]);
+            let weight = x.count_ones();
+            if weight < best_weight {
+                best_weight = weight;
+                best = x.clone();
+            }
+        }
+        Some(best)
+    }
+
+    /// Reduced row echelon form, applying the same row operations to `rhs`
+    fn echelon(&self, rhs: Option<&BitVec>) -> Echelon {
+        if let Some(rhs) = rhs {
+            assert_eq!(
+                rhs.len(),
+                self.rows.len(),
+                "vector length must match the rows"
+            );
+        }
+        let mut rows = self.rows.clone();
+        let mut rhs: Vec<bool> = match rhs {
+            Some(rhs) => (0..rhs.len()).map(|idx| rhs.get(idx)).collect(),
+            None => vec![false; rows.len()],
+        };
+        let mut pivots = Vec::new();
+
+        for col in 0..self.cols {
+            let rank = pivots.len();
+            let Some(found) = (rank..rows.len()).find(|&row| rows[row].get(col)) else {
+                continue;
+            };
+            rows.swap(rank, found);
+            rhs.swap(rank, found);
+            let pivot = rows[rank].clone();
+            for row in 0..rows.len() {
+                if row != rank && rows[row].get(col) {
+                    rows[row].xor_assign(&pivot);
+                    rhs[row] ^= rhs[rank];
+                }
+            }
+            pivots.push(col);
+        }
+
+        Echelon {
+            rows,
+            rhs,
+            pivots,
+            cols: self.cols,
+        }
+    }
+}
+
+/// A matrix in reduced row echelon form with its transformed right-hand side
+struct Echelon {
+    rows: Vec<BitVec>,
+    rhs: Vec<bool>,
+    /// Pivot column of each of the leading rows
+    pivots: Vec<usize>,
+    cols: usize,
+}
+
+impl Echelon {
+    /// The solution with every free variable zero, unless a zero row has a
+    /// nonzero right-hand side
+    fn particular(&self) -> Option<BitVec> {
+        if self.rhs[self.pivots.len()..].iter().any(|&bit| bit) {
+            return None;
+        }
+        let mut x = BitVec::zeros(self.cols);
+        for (row, &col) in self.pivots.iter().enumerate() {
+            x.set(col, self.rhs[row]);
+        }
+        Some(x)
+    }
+
+    /// One vector per free column: that column set, and each pivot variable
+    /// chosen to cancel it
+    fn nullspace(&self) -> Vec<BitVec> {
+        let mut is_pivot = vec![false; self.cols];
+        for &col in &self.pivots {
+            is_pivot[col] = true;
+        }
+        (0..self.cols)
+            .filter(|&free| !is_pivot[free])
+            .map(|free| {
+                let mut x = BitVec::zeros(self.cols);
+                x.toggle(free);
+                for (row, &col) in self.pivots.iter().enumerate() {
+                    if self.rows[row].get(free) {
+                        x.toggle(col);
+                    }
+                }
+                x
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(s: &str) -> BitVec {
+        s.chars().map(|ch| ch == '1').collect()
+    }
+
+    fn matrix(rows: &[&str]) -> Gf2Matrix {
+        Gf2Matrix::from_rows(rows.iter().map(|row| bits(row)).collect())
+    }
+
+    #[test]
+    fn test_bitvec() {
+        let mut v = BitVec::zeros(130);
+        v.set(0, true);
+        v.toggle(64);
+        v.set(129, true);
+        v.set(0, false);
+        assert_eq!(v.ones().collect::<Vec<_>>(), [64, 129]);
+        assert_eq!(v.count_ones(), 2);
+
+        let mut w = BitVec::zeros(130);
+        w.toggle(64);
+        v.xor_assign(&w);
+        assert_eq!(v.ones().collect::<Vec<_>>(), [129]);
+        assert_eq!(bits("0110").to_string(), "0110");
+        assert!(BitVec::zeros(3).is_zero());
+    }
+
+    #[test]
+    fn test_rank_and_nullspace() {
+        let m = matrix(&["110", "011", "101"]);
+        assert_eq!(m.rank(), 2, "third row is the sum of the others");
+
+        let basis = m.nullspace();
+        assert_eq!(basis, [bits("111")]);
+        assert!(m.mul_vec(&basis[0]).is_zero());
+
+        assert_eq!(Gf2Matrix::zeros(2, 3).rank(), 0);
+        assert_eq!(Gf2Matrix::zeros(2, 3).nullspace().len(), 3);
+    }
+
+    #[test]
+    fn test_solve() {
+        let m = matrix(&["110", "011", "101"]);
+        let x = m.solve(&bits("101")).unwrap();
+        assert_eq!(m.mul_vec(&x), bits("101"));
+        assert_eq!(m.solve(&bits("100")), None, "rows sum to 0 but rhs to 1");
+    }
+
+    #[test]
+    fn test_min_weight_solution() {
+        // Columns are the buttons of the first day 10 example machine
+        let m = Gf2Matrix::from_columns(
+            4,
+            &[
+                bits("0001"),
+                bits("0101"),
+                bits("0010"),
+                bits("0011"),
+                bits("1010"),
+                bits("1100"),
+            ],
+        );
+        let target = bits("0110");
+        let x = m.min_weight_solution(&target).unwrap();
+        assert_eq!(x.count_ones(), 2);
+        assert_eq!(m.mul_vec(&x), target);
+    }
+
+    #[test]
+    fn test_wide_matrix() {
+        // More columns than fit in a u32 mask: x_i + x_{i+1} = 1 for each i
+        let n = 70;
+        let mut m = Gf2Matrix::zeros(n - 1, n);
+        for row in 0..n - 1 {
+            m.set(row, row, true);
+            m.set(row, row + 1, true);
+        }
+        let b: BitVec = std::iter::repeat_n(true, n - 1).collect();
+        let x = m.min_weight_solution(&b).unwrap();
+        assert_eq!(x.count_ones(), n / 2);
+        assert_eq!(m.mul_vec(&x), b);
+    }
+}
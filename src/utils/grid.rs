@@ -0,0 +1,312 @@
+//! Rectangular 2D grid with flat storage
+//!
+//! Positions are `(row, col)` pairs with `(0, 0)` at the top-left, matching
+//! how the puzzle inputs are read.
+
+use super::geom::{Direction, Direction8};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// A `(row, col)` position in a grid
+pub type Pos = (usize, usize);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A `width` x `height` grid with every cell set to `fill`
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Swap rows and columns
+    pub fn transpose(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |(row, col)| {
+            self[(col, row)].clone()
+        })
+    }
+
+    /// Rotate a quarter turn clockwise
+    pub fn rotate_cw(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |(row, col)| {
+            self[(self.height - 1 - col, row)].clone()
+        })
+    }
+
+    /// Rotate a quarter turn counter-clockwise
+    pub fn rotate_ccw(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |(row, col)| {
+            self[(col, self.width - 1 - row)].clone()
+        })
+    }
+
+    /// Mirror left to right
+    pub fn flip_horizontal(&self) -> Self {
+        Grid::from_fn(self.width, self.height, |(row, col)| {
+            self[(row, self.width - 1 - col)].clone()
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    /// Build a grid from row-major cells
+    ///
+    /// Panics if `cells` does not hold exactly `width * height` values.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "grid cells don't match its dimensions"
+        );
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Build a grid by computing each cell from its position
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(&mut f)
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The cell at `pos`, or `None` if it lies outside the grid
+    pub fn get(&self, (row, col): Pos) -> Option<&T> {
+        (row < self.height && col < self.width).then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, (row, col): Pos) -> Option<&mut T> {
+        (row < self.height && col < self.width).then(|| &mut self.cells[row * self.width + col])
+    }
+
+    /// `(row, col)` as a position, if it lies inside the grid
+    pub fn position(&self, row: isize, col: isize) -> Option<Pos> {
+        let (row, col) = (usize::try_from(row).ok()?, usize::try_from(col).ok()?);
+        (row < self.height && col < self.width).then_some((row, col))
+    }
+
+    /// The position `delta` away from `pos`, if it lies inside the grid
+    pub fn step(&self, (row, col): Pos, (dr, dc): (isize, isize)) -> Option<Pos> {
+        self.position(row as isize + dr, col as isize + dc)
+    }
+
+    /// The neighbour of `pos` in `dir`, if it lies inside the grid
+    pub fn step_dir(&self, pos: Pos, dir: impl Into<Direction8>) -> Option<Pos> {
+        self.step(pos, dir.into().delta())
+    }
+
+    /// Orthogonal neighbours of `pos` that lie inside the grid, clockwise from up
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step_dir(pos, dir))
+    }
+
+    /// All surrounding cells of `pos`, diagonals included, that lie inside the
+    /// grid, clockwise from up
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction8::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step_dir(pos, dir))
+    }
+
+    /// The cells of one row, left to right
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    /// Every row, top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |row| self.row(row))
+    }
+
+    /// The cells of one column, top to bottom
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "column {} out of range", col);
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    /// Every column, left to right
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |col| self.column(col))
+    }
+
+    /// Every position in reading order
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// Every cell with its position, in reading order
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// Apply `f` to every cell
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Position of the first cell equal to `value`, in reading order
+    pub fn find(&self, value: &T) -> Option<Pos> {
+        self.find_all(value).next()
+    }
+
+    /// Positions of every cell equal to `value`, in reading order
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Pos> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(pos, _)| pos)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} outside {}x{} grid", pos, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} outside {}x{} grid", pos, width, height))
+    }
+}
+
+/// One line per row with cells written back to back, e.g. the puzzle's own
+/// layout for a `Grid<char>`
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, row) in self.rows().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3 wide, 2 high:
+    /// abc
+    /// def
+    fn letters() -> Grid<char> {
+        Grid::from_vec(3, 2, "abcdef".chars().collect())
+    }
+
+    #[test]
+    fn test_get_and_index() {
+        let mut grid = letters();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get((1, 2)), Some(&'f'));
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+
+        grid[(0, 1)] = 'B';
+        assert_eq!(grid[(0, 1)], 'B');
+    }
+
+    #[test]
+    fn test_position_and_step() {
+        let grid = letters();
+        assert_eq!(grid.position(1, 1), Some((1, 1)));
+        assert_eq!(grid.position(-1, 1), None);
+        assert_eq!(grid.step((0, 0), (1, 2)), Some((1, 2)));
+        assert_eq!(grid.step((0, 0), (0, -1)), None);
+        assert_eq!(grid.step_dir((0, 0), Direction8::DownRight), Some((1, 1)));
+        assert_eq!(grid.step_dir((0, 0), Direction::Up), None);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = letters();
+        let four: Vec<Pos> = grid.neighbors4((0, 1)).collect();
+        assert_eq!(four, [(0, 2), (1, 1), (0, 0)]);
+
+        let eight: Vec<Pos> = grid.neighbors8((1, 0)).collect();
+        assert_eq!(eight, [(0, 0), (0, 1), (1, 1)]);
+        assert_eq!(Grid::new(3, 3, 0).neighbors8((1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = letters();
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.rows().count(), 2);
+
+        let columns: Vec<String> = grid.columns().map(|col| col.collect()).collect();
+        assert_eq!(columns, ["ad", "be", "cf"]);
+    }
+
+    #[test]
+    fn test_find() {
+        let grid = Grid::from_vec(2, 2, vec!['.', 'S', '.', 'S']);
+        assert_eq!(grid.find(&'S'), Some((0, 1)));
+        assert_eq!(grid.find_all(&'S').collect::<Vec<_>>(), [(0, 1), (1, 1)]);
+        assert_eq!(grid.find(&'x'), None);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = letters();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+    }
+
+    #[test]
+    fn test_map_and_display() {
+        let grid = Grid::from_fn(2, 2, |(row, col)| row == col);
+        assert_eq!(
+            grid.map(|&on| if on { '#' } else { '.' }).to_string(),
+            "#.\n.#"
+        );
+        assert_eq!(Grid::<char>::from_vec(0, 0, vec![]).to_string(), "");
+    }
+}
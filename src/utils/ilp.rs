@@ -0,0 +1,484 @@
+//! Exact integer linear programming
+//!
+//! Solves `min c·x` subject to `Ax = b`, `x ≥ 0` and `x` integral. Each LP
+//! relaxation is solved with a two-phase simplex over [`Rational`]s, using
+//! Bland's rule so it can't cycle, and fractional variables are split by
+//! branch-and-bound. Nodes whose relaxation can't beat the best integer
+//! solution found so far are pruned.
+//!
+//! The search keeps its open nodes on an explicit stack and never looks past
+//! a bound that some optimal point is known to lie within, so it always
+//! finishes, though that bound is astronomical for large coefficients.
+
+use super::num::Rational;
+use std::fmt;
+
+/// Why a problem has no optimal solution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IlpError {
+    /// No point satisfies the constraints
+    Infeasible,
+    /// Feasible points exist with arbitrarily low objective
+    Unbounded,
+    /// The optimal objective doesn't fit in an `i64`
+    Overflow,
+}
+
+impl fmt::Display for IlpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IlpError::Infeasible => write!(f, "no solution satisfies the constraints"),
+            IlpError::Unbounded => write!(f, "the objective is unbounded"),
+            IlpError::Overflow => write!(f, "the optimum does not fit in 64 bits"),
+        }
+    }
+}
+
+/// An optimal integer point and its objective value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IlpSolution {
+    pub objective: i64,
+    pub x: Vec<i64>,
+}
+
+/// An optimal point of the LP relaxation, where `x` may be fractional
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LpSolution {
+    pub objective: Rational,
+    pub x: Vec<Rational>,
+}
+
+/// Minimise `c·x` over non-negative integer `x` with `a·x = b`
+///
+/// `a` has one row per constraint and one column per variable. Panics if
+/// the dimensions of `a`, `b` and `c` don't agree.
+pub fn minimize(a: &[Vec<i64>], b: &[i64], c: &[i64]) -> Result<IlpSolution, IlpError> {
+    let problem = Problem::new(a, b, c);
+    // A finite optimum is reached at a point within the same bound, with the
+    // objective counted as one more constraint row
+    let mut rows = a.to_vec();
+    rows.push(c.to_vec());
+    let mut rhs = b.to_vec();
+    rhs.push(0);
+    match problem.search(existence_bound(&rows, &rhs)) {
+        Err(IlpError::Unbounded) => {
+            // An unbounded relaxation only makes the integer problem unbounded
+            // if it has an integer point at all. If it does, it has one no
+            // larger than n(ma)^(2m+1) (Papadimitriou, 1981), so the search
+            // for one can stop there.
+            let feasibility = Problem {
+                c: vec![Rational::ZERO; c.len()],
+                ..problem
+            };
+            let found = feasibility.search(existence_bound(a, b))?;
+            Err(found.map_or(IlpError::Infeasible, |_| IlpError::Unbounded))
+        }
+        result => result?.ok_or(IlpError::Infeasible),
+    }
+}
+
+/// Minimise `c·x` over non-negative real `x` with `a·x = b`
+///
+/// Panics if the dimensions of `a`, `b` and `c` don't agree.
+pub fn minimize_relaxation(a: &[Vec<i64>], b: &[i64], c: &[i64]) -> Result<LpSolution, IlpError> {
+    let problem = Problem::new(a, b, c);
+    problem.relax(&Bounds::new(c.len()))
+}
+
+struct Problem {
+    a: Vec<Vec<Rational>>,
+    b: Vec<Rational>,
+    c: Vec<Rational>,
+}
+
+/// Extra bounds on each variable added while branching
+#[derive(Debug, Clone)]
+struct Bounds {
+    lower: Vec<i64>,
+    upper: Vec<Option<i64>>,
+}
+
+impl Bounds {
+    fn new(n: usize) -> Self {
+        Bounds {
+            lower: vec![0; n],
+            upper: vec![None; n],
+        }
+    }
+}
+
+/// A bound on every variable of some integer point of `a·x = b`, if any
+/// exist, saturating at `i64::MAX`
+fn existence_bound(a: &[Vec<i64>], b: &[i64]) -> i64 {
+    let m = a.len() as i64;
+    let n = a.first().map_or(0, Vec::len) as i64;
+    let largest = a
+        .iter()
+        .flatten()
+        .chain(b)
+        .map(|v| v.abs())
+        .max()
+        .unwrap_or(0);
+    let base = m.saturating_mul(largest);
+    (0..2 * m + 1).fold(n, |acc, _| acc.saturating_mul(base))
+}
+
+impl Problem {
+    fn new(a: &[Vec<i64>], b: &[i64], c: &[i64]) -> Self {
+        assert_eq!(a.len(), b.len(), "one right-hand side per constraint");
+        assert!(
+            a.iter().all(|row| row.len() == c.len()),
+            "one coefficient per variable in every constraint"
+        );
+        let rational = |row: &[i64]| row.iter().map(|&v| v.into()).collect();
+        Problem {
+            a: a.iter().map(|row| rational(row)).collect(),
+            b: rational(b),
+            c: rational(c),
+        }
+    }
+
+    /// Depth-first branch-and-bound over the points with no variable past
+    /// `limit`, returning the best integer point found
+    fn search(&self, limit: i64) -> Result<Option<IlpSolution>, IlpError> {
+        let mut best: Option<IlpSolution> = None;
+        let mut open = vec![Bounds::new(self.c.len())];
+        while let Some(bounds) = open.pop() {
+            let lp = match self.relax(&bounds) {
+                Ok(lp) => lp,
+                Err(IlpError::Infeasible) => continue,
+                Err(err) => return Err(err),
+            };
+            // The objective of an integer point is an integer, so the relaxation
+            // has to come in at least one below the best to be worth exploring
+            if best
+                .as_ref()
+                .is_some_and(|best| lp.objective.ceil() >= best.objective.into())
+            {
+                continue;
+            }
+
+            // Nothing past the limit needs searching, so cap a variable that
+            // goes beyond it rather than branching on it
+            if let Some(j) = lp.x.iter().position(|&v| v > limit.into()) {
+                let mut capped = bounds;
+                capped.upper[j] = Some(limit);
+                open.push(capped);
+                continue;
+            }
+
+            let Some(j) = lp.x.iter().position(|v| !v.is_integer()) else {
+                let x = lp
+                    .x
+                    .iter()
+                    .map(|v| v.to_integer())
+                    .collect::<Option<Vec<i64>>>()
+                    .ok_or(IlpError::Overflow)?;
+                let objective = lp.objective.to_integer().ok_or(IlpError::Overflow)?;
+                best = Some(IlpSolution { objective, x });
+                continue;
+            };
+
+            // Below the limit, so it fits; the branch below it is explored first
+            let floor = lp.x[j].floor() as i64;
+            let mut raised = bounds.clone();
+            raised.lower[j] = floor + 1;
+            open.push(raised);
+            let mut lowered = bounds;
+            lowered.upper[j] = Some(floor);
+            open.push(lowered);
+        }
+        Ok(best)
+    }
+
+    /// Solve the LP relaxation with `bounds` added as extra constraints
+    fn relax(&self, bounds: &Bounds) -> Result<LpSolution, IlpError> {
+        let n = self.c.len();
+        // Each bound becomes an equality with its own slack variable
+        let mut extra: Vec<(usize, i64, Rational)> = Vec::new();
+        for j in 0..n {
+            if bounds.lower[j] > 0 {
+                extra.push((j, bounds.lower[j], -Rational::ONE));
+            }
+            if let Some(upper) = bounds.upper[j] {
+                extra.push((j, upper, Rational::ONE));
+            }
+        }
+        let width = n + extra.len();
+
+        let mut a: Vec<Vec<Rational>> = self
+            .a
+            .iter()
+            .map(|row| {
+                let mut row = row.clone();
+                row.resize(width, Rational::ZERO);
+                row
+            })
+            .collect();
+        let mut b = self.b.clone();
+        for (idx, &(j, bound, slack)) in extra.iter().enumerate() {
+            let mut row = vec![Rational::ZERO; width];
+            row[j] = Rational::ONE;
+            row[n + idx] = slack;
+            a.push(row);
+            b.push(bound.into());
+        }
+        let mut c = self.c.clone();
+        c.resize(width, Rational::ZERO);
+
+        let mut lp = simplex(&a, &b, &c)?;
+        lp.x.truncate(n);
+        Ok(lp)
+    }
+}
+
+/// Simplex tableau: constraint rows with the right-hand side last, the
+/// reduced costs with minus the objective value last, and the basic variable
+/// of each row
+struct Tableau {
+    rows: Vec<Vec<Rational>>,
+    costs: Vec<Rational>,
+    basis: Vec<usize>,
+}
+
+impl Tableau {
+    fn pivot(&mut self, r: usize, col: usize) {
+        let pivot = self.rows[r][col];
+        for cell in &mut self.rows[r] {
+            *cell /= pivot;
+        }
+        let pivot_row = self.rows[r].clone();
+        let eliminate = |row: &mut Vec<Rational>| {
+            let factor = row[col];
+            if !factor.is_zero() {
+                for (cell, &p) in row.iter_mut().zip(&pivot_row) {
+                    *cell -= factor * p;
+                }
+            }
+        };
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            if i != r {
+                eliminate(row);
+            }
+        }
+        eliminate(&mut self.costs);
+        self.basis[r] = col;
+    }
+
+    /// Pivot until no variable below `cols` has a negative reduced cost
+    fn optimize(&mut self, cols: usize) -> Result<(), IlpError> {
+        let rhs = self.costs.len() - 1;
+        // Bland's rule: lowest entering column, then lowest leaving variable
+        while let Some(col) = (0..cols).find(|&j| self.costs[j] < Rational::ZERO) {
+            let mut leave: Option<(usize, Rational)> = None;
+            for (i, row) in self.rows.iter().enumerate() {
+                if row[col] <= Rational::ZERO {
+                    continue;
+                }
+                let ratio = row[rhs] / row[col];
+                let better = match leave {
+                    None => true,
+                    Some((best, best_ratio)) => {
+                        ratio < best_ratio
+                            || (ratio == best_ratio && self.basis[i] < self.basis[best])
+                    }
+                };
+                if better {
+                    leave = Some((i, ratio));
+                }
+            }
+            let (r, _) = leave.ok_or(IlpError::Unbounded)?;
+            self.pivot(r, col);
+        }
+        Ok(())
+    }
+}
+
+/// Two-phase simplex for `min c·x` with `a·x = b` and `x ≥ 0`
+fn simplex(a: &[Vec<Rational>], b: &[Rational], c: &[Rational]) -> Result<LpSolution, IlpError> {
+    let (m, n) = (a.len(), c.len());
+
+    // Phase 1: one artificial variable per row, minimising their sum
+    let rows: Vec<Vec<Rational>> = a
+        .iter()
+        .zip(b)
+        .enumerate()
+        .map(|(i, (row, &rhs))| {
+            let sign = if rhs < Rational::ZERO {
+                -Rational::ONE
+            } else {
+                Rational::ONE
+            };
+            let mut tableau_row: Vec<Rational> = row.iter().map(|&v| v * sign).collect();
+            tableau_row.extend((0..m).map(|k| {
+                if k == i {
+                    Rational::ONE
+                } else {
+                    Rational::ZERO
+                }
+            }));
+            tableau_row.push(rhs * sign);
+            tableau_row
+        })
+        .collect();
+    let mut costs = vec![Rational::ZERO; n + m + 1];
+    for row in &rows {
+        for (j, &cell) in row.iter().enumerate() {
+            if j < n || j == n + m {
+                costs[j] -= cell;
+            }
+        }
+    }
+    let mut tableau = Tableau {
+        rows,
+        costs,
+        basis: (n..n + m).collect(),
+    };
+    tableau.optimize(n + m)?;
+    if !tableau.costs[n + m].is_zero() {
+        return Err(IlpError::Infeasible);
+    }
+
+    // Swap any artificial variables left in the basis for real ones; a row
+    // with no real variable left was redundant
+    let mut i = 0;
+    while i < tableau.rows.len() {
+        if tableau.basis[i] >= n {
+            match (0..n).find(|&j| !tableau.rows[i][j].is_zero()) {
+                Some(j) => tableau.pivot(i, j),
+                None => {
+                    tableau.rows.remove(i);
+                    tableau.basis.remove(i);
+                    continue;
+                }
+            }
+        }
+        i += 1;
+    }
+    for row in &mut tableau.rows {
+        row.drain(n..n + m);
+    }
+
+    // Phase 2: the real objective, priced out against the current basis
+    let mut costs: Vec<Rational> = c.to_vec();
+    costs.push(Rational::ZERO);
+    for (row, &basic) in tableau.rows.iter().zip(&tableau.basis) {
+        let factor = c[basic];
+        if !factor.is_zero() {
+            for (cost, &cell) in costs.iter_mut().zip(row) {
+                *cost -= factor * cell;
+            }
+        }
+    }
+    tableau.costs = costs;
+    tableau.optimize(n)?;
+
+    let mut x = vec![Rational::ZERO; n];
+    for (row, &basic) in tableau.rows.iter().zip(&tableau.basis) {
+        x[basic] = row[n];
+    }
+    Ok(LpSolution {
+        objective: -tableau.costs[n],
+        x,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(a: &[Vec<i64>], b: &[i64], solution: &IlpSolution) {
+        for (row, &rhs) in a.iter().zip(b) {
+            let lhs: i64 = row.iter().zip(&solution.x).map(|(a, x)| a * x).sum();
+            assert_eq!(lhs, rhs);
+        }
+        assert!(solution.x.iter().all(|&x| x >= 0));
+    }
+
+    #[test]
+    fn test_unique_solution() {
+        // x + y = 3, x - y = 1
+        let a = vec![vec![1, 1], vec![1, -1]];
+        let solution = minimize(&a, &[3, 1], &[1, 1]).unwrap();
+        assert_eq!(
+            solution,
+            IlpSolution {
+                objective: 3,
+                x: vec![2, 1]
+            }
+        );
+    }
+
+    #[test]
+    fn test_branching_beats_relaxation() {
+        // 2x + 2y + z = 5 with z costing far more: the relaxation picks
+        // x = 2.5, but an integer point needs z = 1
+        let a = vec![vec![2, 2, 1]];
+        let relaxed = minimize_relaxation(&a, &[5], &[1, 1, 10]).unwrap();
+        assert_eq!(relaxed.objective, Rational::new(5, 2));
+
+        let solution = minimize(&a, &[5], &[1, 1, 10]).unwrap();
+        assert_eq!(solution.objective, 12);
+        check(&a, &[5], &solution);
+    }
+
+    #[test]
+    fn test_button_presses() {
+        // Day 10's first example machine: buttons as columns, counters as rows
+        let buttons: [&[usize]; 6] = [&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]];
+        let mut a = vec![vec![0; buttons.len()]; 4];
+        for (col, button) in buttons.iter().enumerate() {
+            for &row in *button {
+                a[row][col] = 1;
+            }
+        }
+        let b = [3, 5, 4, 7];
+        let solution = minimize(&a, &b, &[1; 6]).unwrap();
+        assert_eq!(solution.objective, 10);
+        check(&a, &b, &solution);
+    }
+
+    #[test]
+    fn test_redundant_constraints() {
+        let a = vec![vec![1, 1], vec![2, 2]];
+        let solution = minimize(&a, &[4, 8], &[3, 1]).unwrap();
+        assert_eq!(
+            solution,
+            IlpSolution {
+                objective: 4,
+                x: vec![0, 4]
+            }
+        );
+    }
+
+    #[test]
+    fn test_infeasible() {
+        // x + y = -1 has no non-negative solution
+        assert_eq!(
+            minimize(&[vec![1, 1]], &[-1], &[1, 1]),
+            Err(IlpError::Infeasible)
+        );
+        // 2x = 3 has a real solution but no integer one
+        assert!(minimize_relaxation(&[vec![2]], &[3], &[1]).is_ok());
+        assert_eq!(minimize(&[vec![2]], &[3], &[1]), Err(IlpError::Infeasible));
+    }
+
+    #[test]
+    fn test_unbounded() {
+        // x - y = 0 lets both grow forever while the objective falls
+        let a = vec![vec![1, -1]];
+        assert_eq!(minimize(&a, &[0], &[-1, 0]), Err(IlpError::Unbounded));
+        // ... but not if no integer point exists at all
+        let a = vec![vec![2, -2]];
+        assert_eq!(minimize(&a, &[1], &[-1, 0]), Err(IlpError::Infeasible));
+    }
+
+    #[test]
+    fn test_no_integer_point_in_unbounded_region() {
+        // 2x - 2y = 1 has real points as large as you like but no integer one;
+        // with a bounded objective the search still has to give up somewhere
+        let a = vec![vec![2, -2]];
+        assert!(minimize_relaxation(&a, &[1], &[1, 1]).is_ok());
+        assert_eq!(minimize(&a, &[1], &[1, 1]), Err(IlpError::Infeasible));
+    }
+}
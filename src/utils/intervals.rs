@@ -0,0 +1,249 @@
+//! Sets of integers stored as sorted, disjoint inclusive spans
+//!
+//! Spans that overlap or touch are merged as they are added, so `3-5` and
+//! `6-9` are kept as the single span `3-9`. Lookups binary-search the spans.
+//! Building a set from many ranges at once sorts them and merges in one pass.
+
+/// A set of `i64`s made of inclusive `(start, end)` spans
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet {
+    /// Sorted by start, with a gap of at least one between neighbours
+    spans: Vec<(i64, i64)>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    /// Add every integer in `start..=end`; does nothing if `start > end`
+    pub fn insert(&mut self, start: i64, end: i64) {
+        if start > end {
+            return;
+        }
+        // Spans from `first` up to `last` overlap or touch the new one
+        let first = self
+            .spans
+            .partition_point(|&(_, e)| e.saturating_add(1) < start);
+        let last = self
+            .spans
+            .partition_point(|&(s, _)| s <= end.saturating_add(1));
+        let merged = if first < last {
+            (
+                start.min(self.spans[first].0),
+                end.max(self.spans[last - 1].1),
+            )
+        } else {
+            (start, end)
+        };
+        self.spans.splice(first..last, [merged]);
+    }
+
+    /// Take out every integer in `start..=end`; does nothing if `start > end`
+    pub fn remove(&mut self, start: i64, end: i64) {
+        if start > end {
+            return;
+        }
+        // Spans from `first` up to `last` overlap the removed range
+        let first = self.spans.partition_point(|&(_, e)| e < start);
+        let last = self.spans.partition_point(|&(s, _)| s <= end);
+        if first == last {
+            return;
+        }
+        let mut kept = Vec::with_capacity(2);
+        let (s, _) = self.spans[first];
+        if s < start {
+            kept.push((s, start - 1));
+        }
+        let (_, e) = self.spans[last - 1];
+        if e > end {
+            kept.push((end + 1, e));
+        }
+        self.spans.splice(first..last, kept);
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let idx = self.spans.partition_point(|&(_, e)| e < value);
+        self.spans.get(idx).is_some_and(|&(s, _)| s <= value)
+    }
+
+    /// Every integer in either set
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = self.clone();
+        for (start, end) in other.iter() {
+            result.insert(start, end);
+        }
+        result
+    }
+
+    /// Every integer in both sets
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut spans = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.spans.len() && j < other.spans.len() {
+            let (a_start, a_end) = self.spans[i];
+            let (b_start, b_end) = other.spans[j];
+            let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+            if start <= end {
+                spans.push((start, end));
+            }
+            // Whichever span ends first can't meet anything else
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { spans }
+    }
+
+    /// Every integer in this set but not in `other`
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = self.clone();
+        for (start, end) in other.iter() {
+            result.remove(start, end);
+        }
+        result
+    }
+
+    /// Number of integers in the set
+    ///
+    /// Wider than `i64` since the set can hold all 2^64 of them.
+    pub fn total_len(&self) -> u128 {
+        self.spans
+            .iter()
+            .map(|&(s, e)| (i128::from(e) - i128::from(s) + 1) as u128)
+            .sum()
+    }
+
+    /// Number of merged spans
+    pub fn span_count(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// The merged spans in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.spans.iter().copied()
+    }
+}
+
+impl FromIterator<(i64, i64)> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = (i64, i64)>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<(i64, i64)> for IntervalSet {
+    /// Add every span at once, skipping empty ones, with a single sort and merge
+    fn extend<I: IntoIterator<Item = (i64, i64)>>(&mut self, iter: I) {
+        let mut sorted = std::mem::take(&mut self.spans);
+        sorted.extend(iter.into_iter().filter(|&(start, end)| start <= end));
+        sorted.sort_unstable();
+
+        let mut spans: Vec<(i64, i64)> = Vec::with_capacity(sorted.len());
+        for (start, end) in sorted {
+            match spans.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+                _ => spans.push((start, end)),
+            }
+        }
+        self.spans = spans;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(set: &IntervalSet) -> Vec<(i64, i64)> {
+        set.iter().collect()
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let set: IntervalSet = [(10, 14), (3, 5), (16, 20), (12, 18)].into_iter().collect();
+        assert_eq!(spans(&set), [(3, 5), (10, 20)]);
+        assert_eq!(set.total_len(), 14);
+
+        let mut set = IntervalSet::new();
+        set.insert(1, 3);
+        set.insert(4, 6);
+        set.insert(9, 8);
+        assert_eq!(
+            spans(&set),
+            [(1, 6)],
+            "adjacent spans merge, empty ones are ignored"
+        );
+        set.insert(0, 20);
+        assert_eq!(spans(&set), [(0, 20)]);
+    }
+
+    #[test]
+    fn test_extend_merges() {
+        let mut set: IntervalSet = [(1, 2), (30, 40)].into_iter().collect();
+        set.extend([(25, 29), (7, 6), (4, 5), (3, 3), (35, 50), (60, 60)]);
+        assert_eq!(spans(&set), [(1, 5), (25, 50), (60, 60)]);
+        assert_eq!(set.total_len(), 32);
+
+        // Many unsorted spans, each merging with the next only once sorted
+        let set: IntervalSet = (0..10_000).rev().map(|i| (2 * i, 2 * i + 1)).collect();
+        assert_eq!(spans(&set), [(0, 19_999)]);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut set: IntervalSet = [(0, 10), (20, 30)].into_iter().collect();
+        set.remove(5, 22);
+        assert_eq!(spans(&set), [(0, 4), (23, 30)]);
+        set.remove(25, 26);
+        assert_eq!(spans(&set), [(0, 4), (23, 24), (27, 30)]);
+        set.remove(-5, 100);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_contains() {
+        let set: IntervalSet = [(3, 5), (10, 20)].into_iter().collect();
+        let fresh: Vec<i64> = [1, 3, 5, 8, 11, 20, 21]
+            .into_iter()
+            .filter(|&id| set.contains(id))
+            .collect();
+        assert_eq!(fresh, [3, 5, 11, 20]);
+        assert!(!IntervalSet::new().contains(0));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: IntervalSet = [(0, 10), (20, 30)].into_iter().collect();
+        let b: IntervalSet = [(5, 25), (40, 40)].into_iter().collect();
+        assert_eq!(spans(&a.union(&b)), [(0, 30), (40, 40)]);
+        assert_eq!(spans(&a.intersection(&b)), [(5, 10), (20, 25)]);
+        assert_eq!(spans(&a.difference(&b)), [(0, 4), (26, 30)]);
+        assert_eq!(spans(&b.difference(&a)), [(11, 19), (40, 40)]);
+        assert_eq!(a.union(&b).span_count(), 2);
+    }
+
+    #[test]
+    fn test_extremes() {
+        let mut set = IntervalSet::new();
+        set.insert(i64::MAX - 1, i64::MAX);
+        set.insert(i64::MIN, i64::MIN + 1);
+        assert!(set.contains(i64::MAX));
+        set.remove(i64::MAX, i64::MAX);
+        assert_eq!(
+            spans(&set),
+            [(i64::MIN, i64::MIN + 1), (i64::MAX - 1, i64::MAX - 1)]
+        );
+        assert_eq!(set.total_len(), 3);
+
+        let everything: IntervalSet = [(i64::MIN, -1), (0, i64::MAX)].into_iter().collect();
+        assert_eq!(spans(&everything), [(i64::MIN, i64::MAX)]);
+        assert_eq!(everything.total_len(), 1 << 64);
+    }
+}
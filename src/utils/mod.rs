@@ -0,0 +1,194 @@
+//! Common utilities used across multiple days
+
+pub mod dlx;
+pub mod dsu;
+pub mod geom;
+pub mod gf2;
+pub mod grid;
+pub mod ilp;
+pub mod intervals;
+pub mod num;
+pub mod polyomino;
+
+pub use dsu::UnionFind;
+pub use geom::{Bounds2, Direction, Direction8, Point2, Point3};
+pub use grid::Grid;
+pub use intervals::IntervalSet;
+
+use crate::error::{parse_number, ParseError};
+use std::str::FromStr;
+
+/// Parse a rectangular grid of characters
+pub fn parse_char_grid(input: &str) -> Result<Grid<char>, ParseError> {
+    parse_cells(input, |_| true)
+}
+
+/// Parse a rectangular grid, rejecting characters outside `allowed`
+pub fn parse_grid(input: &str, allowed: &[char]) -> Result<Grid<char>, ParseError> {
+    parse_cells(input, |ch| allowed.contains(&ch))
+}
+
+fn parse_cells(input: &str, accept: impl Fn(char) -> bool) -> Result<Grid<char>, ParseError> {
+    let mut cells = Vec::new();
+    let mut width = None;
+    let mut height = 0;
+    for (idx, line) in input.lines().enumerate() {
+        let row: Vec<char> = line.chars().collect();
+        if let Some(col) = row.iter().position(|&ch| !accept(ch)) {
+            return Err(ParseError::new(
+                idx + 1,
+                col + 1,
+                format!("unexpected character '{}'", row[col]),
+            ));
+        }
+        let expected = *width.get_or_insert(row.len());
+        if row.len() != expected {
+            return Err(ParseError::new(
+                idx + 1,
+                row.len().min(expected) + 1,
+                format!("expected {} columns, found {}", expected, row.len()),
+            ));
+        }
+        cells.extend(row);
+        height += 1;
+    }
+    Ok(Grid::from_vec(width.unwrap_or(0), height, cells))
+}
+
+/// Parse a list of integers from lines
+pub fn parse_int_lines(input: &str) -> Vec<i64> {
+    input
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect()
+}
+
+/// Parse each non-blank line with `f`, stopping at the first error
+///
+/// `f` receives the 1-based line number and the line itself.
+pub fn parse_lines<T>(
+    input: &str,
+    mut f: impl FnMut(usize, &str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| f(idx + 1, line))
+        .collect()
+}
+
+/// Parse a line of exactly `count` comma-separated numbers
+pub fn parse_csv_numbers<T: FromStr>(
+    line_no: usize,
+    line: &str,
+    count: usize,
+) -> Result<Vec<T>, ParseError> {
+    let fields: Vec<&str> = line.split(',').collect();
+    if fields.len() != count {
+        return Err(ParseError::at(
+            line_no,
+            line,
+            line.trim_start(),
+            format!(
+                "expected {} comma-separated numbers, found {}",
+                count,
+                fields.len()
+            ),
+        ));
+    }
+    fields
+        .iter()
+        .map(|field| parse_number(line_no, line, field))
+        .collect()
+}
+
+/// Like [`parse_lines`], but silently skips lines that fail to parse
+pub fn parse_lines_lenient<T>(
+    input: &str,
+    mut f: impl FnMut(usize, &str) -> Result<T, ParseError>,
+) -> Vec<T> {
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| f(idx + 1, line).ok())
+        .collect()
+}
+
+/// Split input by blank lines
+pub fn split_by_blank_lines(input: &str) -> Vec<&str> {
+    input.split("\n\n").collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_char_grid() {
+        let input = "abc\ndef\nghi";
+        let grid = parse_char_grid(input).unwrap();
+
+        assert_eq!(grid.height(), 3);
+        assert_eq!(grid.row(0), ['a', 'b', 'c']);
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.row(2), ['g', 'h', 'i']);
+        assert_eq!(grid.to_string(), input);
+    }
+
+    #[test]
+    fn test_parse_grid() {
+        assert_eq!(
+            parse_grid(".#\n#.", &['.', '#']),
+            Ok(Grid::from_vec(2, 2, vec!['.', '#', '#', '.']))
+        );
+
+        let err = parse_grid(".#\n#x", &['.', '#']).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+
+        let err = parse_grid(".#\n#", &['.', '#']).unwrap_err();
+        assert_eq!(err.message, "expected 2 columns, found 1");
+    }
+
+    #[test]
+    fn test_parse_int_lines() {
+        let input = "1\n2\n3\n4\n5";
+        let numbers = parse_int_lines(input);
+
+        assert_eq!(numbers, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_parse_lines_reports_line_number() {
+        let input = "1\n\nx\n3";
+        let parse =
+            |line_no: usize, line: &str| crate::error::parse_number::<i64>(line_no, line, line);
+
+        assert_eq!(parse_lines("1\n\n3", parse), Ok(vec![1, 3]));
+        assert_eq!(parse_lines(input, parse).unwrap_err().line, 3);
+        assert_eq!(parse_lines_lenient(input, parse), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_parse_csv_numbers() {
+        assert_eq!(parse_csv_numbers::<i32>(1, "1, 2,3", 3), Ok(vec![1, 2, 3]));
+        assert_eq!(
+            parse_csv_numbers::<i32>(5, "1,2", 3).unwrap_err().message,
+            "expected 3 comma-separated numbers, found 2"
+        );
+        assert_eq!(
+            parse_csv_numbers::<i32>(5, "1,b,3", 3).unwrap_err().column,
+            3
+        );
+    }
+
+    #[test]
+    fn test_split_by_blank_lines() {
+        let input = "group1\nline2\n\ngroup2\nline2";
+        let groups = split_by_blank_lines(input);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0], "group1\nline2");
+        assert_eq!(groups[1], "group2\nline2");
+    }
+}
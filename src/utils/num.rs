@@ -0,0 +1,382 @@
+//! Number theory helpers and exact rational arithmetic
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Greatest common divisor, always non-negative; `gcd(0, 0)` is 0
+pub fn gcd(a: i64, b: i64) -> i64 {
+    gcd_i128(a.into(), b.into()) as i64
+}
+
+fn gcd_i128(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+/// Least common multiple, always non-negative; 0 if either argument is
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b) * b).abs()
+}
+
+/// `(g, x, y)` with `g = gcd(a, b)` and `a * x + b * y == g`
+pub fn ext_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = ext_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Division rounding towards negative infinity
+pub fn floor_div(a: i64, b: i64) -> i64 {
+    let q = a / b;
+    if a % b != 0 && (a < 0) != (b < 0) {
+        q - 1
+    } else {
+        q
+    }
+}
+
+/// The Möbius function: 0 if `n` has a squared prime factor, otherwise -1 or
+/// 1 for an odd or even number of prime factors
+///
+/// Panics if `n` is 0.
+pub fn mobius(mut n: u64) -> i64 {
+    assert!(n > 0, "mobius is defined for positive integers");
+    let mut result = 1;
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            n /= p;
+            if n.is_multiple_of(p) {
+                return 0;
+            }
+            result = -result;
+        }
+        p += 1;
+    }
+    if n > 1 {
+        result = -result;
+    }
+    result
+}
+
+/// An exact fraction in lowest terms with a positive denominator
+///
+/// Backed by `i128`; arithmetic that overflows even that panics rather than
+/// silently wrapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    /// `num / den` in lowest terms
+    ///
+    /// Panics if `den` is zero.
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "rational with zero denominator");
+        let g = gcd_i128(num, den);
+        let sign = if den < 0 { -1 } else { 1 };
+        Rational {
+            num: sign * num / g,
+            den: sign * den / g,
+        }
+    }
+
+    pub fn numer(self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(self) -> i128 {
+        self.den
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.num == 0
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.den == 1
+    }
+
+    /// The value as an `i64`, if it is a whole number that fits
+    pub fn to_integer(self) -> Option<i64> {
+        if self.is_integer() {
+            i64::try_from(self.num).ok()
+        } else {
+            None
+        }
+    }
+
+    /// Largest integer not above the value
+    pub fn floor(self) -> i128 {
+        self.num.div_euclid(self.den)
+    }
+
+    /// Smallest integer not below the value
+    pub fn ceil(self) -> i128 {
+        -(-self).floor()
+    }
+
+    /// `1 / self`
+    ///
+    /// Panics if the value is zero.
+    pub fn recip(self) -> Self {
+        Rational::new(self.den, self.num)
+    }
+
+    pub fn abs(self) -> Self {
+        Rational {
+            num: self.num.abs(),
+            den: self.den,
+        }
+    }
+}
+
+fn checked(value: Option<i128>) -> i128 {
+    value.expect("rational arithmetic overflowed i128")
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Rational::ZERO
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Rational {
+            num: n.into(),
+            den: 1,
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, other: Rational) -> Rational {
+        // Scale by the lcm of the denominators to keep intermediates small
+        let g = gcd_i128(self.den, other.den);
+        let (a, b) = (self.den / g, other.den / g);
+        let num = checked(
+            checked(self.num.checked_mul(b)).checked_add(checked(other.num.checked_mul(a))),
+        );
+        Rational::new(num, checked(self.den.checked_mul(b)))
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Rational {
+        self + -other
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, other: Rational) -> Rational {
+        // Cancel across before multiplying
+        let g1 = gcd_i128(self.num, other.den).max(1);
+        let g2 = gcd_i128(other.num, self.den).max(1);
+        Rational::new(
+            checked((self.num / g1).checked_mul(other.num / g2)),
+            checked((self.den / g2).checked_mul(other.den / g1)),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    /// Panics if `other` is zero
+    fn div(self, other: Rational) -> Rational {
+        Mul::mul(self, other.recip())
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl AddAssign for Rational {
+    fn add_assign(&mut self, other: Rational) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Rational {
+    fn sub_assign(&mut self, other: Rational) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign for Rational {
+    fn mul_assign(&mut self, other: Rational) {
+        *self = *self * other;
+    }
+}
+
+impl DivAssign for Rational {
+    fn div_assign(&mut self, other: Rational) {
+        *self = *self / other;
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Denominators are positive, so cross-multiplying keeps the order
+        let lhs = checked(self.num.checked_mul(other.den));
+        let rhs = checked(other.num.checked_mul(self.den));
+        lhs.cmp(&rhs)
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rat(num: i128, den: i128) -> Rational {
+        Rational::new(num, den)
+    }
+
+    #[test]
+    fn test_gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(-4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+    }
+
+    #[test]
+    fn test_ext_gcd_and_inverse() {
+        for (a, b) in [(240, 46), (-7, 3), (0, 5), (17, -51)] {
+            let (g, x, y) = ext_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g, "for {} and {}", a, b);
+        }
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(4, 8), None);
+    }
+
+    #[test]
+    fn test_floor_div() {
+        assert_eq!(floor_div(7, 2), 3);
+        assert_eq!(floor_div(-7, 2), -4);
+        assert_eq!(floor_div(7, -2), -4);
+        assert_eq!(floor_div(-7, -2), 3);
+        assert_eq!(floor_div(-100, 100), -1);
+    }
+
+    #[test]
+    fn test_mobius() {
+        let values: Vec<i64> = (1..=12).map(mobius).collect();
+        assert_eq!(values, [1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0]);
+    }
+
+    #[test]
+    fn test_rational_normalises() {
+        assert_eq!(rat(2, -4), rat(-1, 2));
+        assert_eq!(rat(2, -4).denom(), 2);
+        assert_eq!(rat(0, -5), Rational::ZERO);
+        assert_eq!(rat(6, 3).to_integer(), Some(2));
+        assert_eq!(rat(1, 3).to_integer(), None);
+        assert_eq!(rat(-7, 2).to_string(), "-7/2");
+        assert_eq!(Rational::from(4).to_string(), "4");
+    }
+
+    #[test]
+    fn test_rational_arithmetic() {
+        let (a, b) = (rat(1, 2), rat(1, 3));
+        assert_eq!(a + b, rat(5, 6));
+        assert_eq!(a - b, rat(1, 6));
+        assert_eq!(a * b, rat(1, 6));
+        assert_eq!(a / b, rat(3, 2));
+        assert_eq!(-a, rat(-1, 2));
+
+        let mut c = Rational::ONE;
+        c -= a;
+        c *= rat(4, 1);
+        c /= rat(3, 1);
+        c += b;
+        assert_eq!(c, Rational::ONE);
+
+        assert!(b < a);
+        assert!(rat(-1, 2) < rat(-1, 3));
+        assert_eq!((rat(-7, 2).floor(), rat(-7, 2).ceil()), (-4, -3));
+    }
+
+    #[test]
+    fn test_rational_avoids_i64_overflow() {
+        // Both products overflow i64 but the result is small
+        let big = Rational::from(i64::MAX);
+        assert_eq!((big * big) / (big * big), Rational::ONE);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflowed")]
+    fn test_rational_overflow_panics() {
+        let big = Rational::new(i128::MAX, 1);
+        let _ = big + big;
+    }
+
+    #[test]
+    #[should_panic(expected = "zero denominator")]
+    fn test_rational_division_by_zero() {
+        let _ = Rational::ONE / Rational::ZERO;
+    }
+}
@@ -0,0 +1,365 @@
+//! Polyominoes: shapes made of grid cells, up to translation
+//!
+//! Cells are `Point2<i32>` with `x` the column and `y` the row, always
+//! shifted so the bounding box starts at the origin. A shape can be moved by
+//! any of the eight symmetries of the square; the orientations it can take
+//! are the distinct results, and its canonical form is the smallest of them,
+//! so two shapes are congruent exactly when their canonical forms match.
+
+use crate::error::ParseError;
+use crate::utils::{Bounds2, Point2};
+use std::fmt;
+use std::str::FromStr;
+
+/// One of the eight symmetries of the square: an optional mirror image
+/// (left to right), then some clockwise quarter turns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Transform {
+    pub flip: bool,
+    pub turns: u8,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform::new(false, 0);
+
+    /// The rotations first, then their mirror images
+    pub const ALL: [Transform; 8] = [
+        Transform::new(false, 0),
+        Transform::new(false, 1),
+        Transform::new(false, 2),
+        Transform::new(false, 3),
+        Transform::new(true, 0),
+        Transform::new(true, 1),
+        Transform::new(true, 2),
+        Transform::new(true, 3),
+    ];
+
+    pub const fn new(flip: bool, turns: u8) -> Self {
+        Transform { flip, turns }
+    }
+
+    /// Where this transform sends `p`, about the origin
+    pub fn apply(self, p: Point2<i32>) -> Point2<i32> {
+        let mut p = if self.flip { p.flip_horizontal() } else { p };
+        for _ in 0..self.turns % 4 {
+            p = p.rotate_cw();
+        }
+        p
+    }
+}
+
+/// A set of cells, normalised so the bounding box starts at the origin
+///
+/// Cells are kept in reading order, so equal shapes compare equal.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Polyomino {
+    cells: Vec<Point2<i32>>,
+}
+
+impl Polyomino {
+    /// The shape made by `cells`, wherever they are; duplicates are ignored
+    pub fn new(cells: impl IntoIterator<Item = Point2<i32>>) -> Self {
+        let mut cells: Vec<Point2<i32>> = cells.into_iter().collect();
+        if let Some(bounds) = Bounds2::from_points(cells.iter().copied()) {
+            for p in &mut cells {
+                *p -= bounds.min;
+            }
+        }
+        cells.sort_by_key(|p| (p.y, p.x));
+        cells.dedup();
+        Polyomino { cells }
+    }
+
+    /// The cells in reading order
+    pub fn cells(&self) -> &[Point2<i32>] {
+        &self.cells
+    }
+
+    pub fn contains(&self, p: Point2<i32>) -> bool {
+        self.cells
+            .binary_search_by_key(&(p.y, p.x), |c| (c.y, c.x))
+            .is_ok()
+    }
+
+    /// Number of cells
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Bounding box, starting at the origin; `None` for the empty shape
+    pub fn bounds(&self) -> Option<Bounds2<i32>> {
+        Bounds2::from_points(self.cells.iter().copied())
+    }
+
+    /// Number of columns spanned
+    pub fn width(&self) -> usize {
+        self.bounds().map_or(0, |b| b.width() as usize)
+    }
+
+    /// Number of rows spanned
+    pub fn height(&self) -> usize {
+        self.bounds().map_or(0, |b| b.height() as usize)
+    }
+
+    /// This shape moved by `transform`
+    pub fn transform(&self, transform: Transform) -> Polyomino {
+        Polyomino::new(self.cells.iter().map(|&p| transform.apply(p)))
+    }
+
+    /// Every distinct orientation, each with the first transform in
+    /// `Transform::ALL` that produces it
+    pub fn orientations(&self) -> Vec<(Transform, Polyomino)> {
+        let mut orientations: Vec<(Transform, Polyomino)> = Vec::with_capacity(8);
+        for transform in Transform::ALL {
+            let shape = self.transform(transform);
+            if orientations.iter().all(|(_, seen)| *seen != shape) {
+                orientations.push((transform, shape));
+            }
+        }
+        orientations
+    }
+
+    /// The transforms that leave the shape unchanged
+    ///
+    /// Always includes the identity; the shape has `8 / len` orientations.
+    pub fn symmetries(&self) -> Vec<Transform> {
+        Transform::ALL
+            .into_iter()
+            .filter(|&transform| self.transform(transform) == *self)
+            .collect()
+    }
+
+    /// The smallest orientation, shared by every shape congruent to this one
+    pub fn canonical(&self) -> Polyomino {
+        Transform::ALL
+            .into_iter()
+            .map(|transform| self.transform(transform))
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Whether `other` is this shape in some orientation
+    pub fn is_congruent(&self, other: &Polyomino) -> bool {
+        self.area() == other.area() && self.canonical() == other.canonical()
+    }
+
+    /// A transform taking this shape to `other`, if they are congruent
+    pub fn transform_to(&self, other: &Polyomino) -> Option<Transform> {
+        Transform::ALL
+            .into_iter()
+            .find(|&transform| self.transform(transform) == *other)
+    }
+}
+
+impl fmt::Display for Polyomino {
+    /// The bounding box with `#` for cells and `.` elsewhere
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.height() as i32 {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            for col in 0..self.width() as i32 {
+                let filled = self.contains(Point2::new(col, row));
+                f.write_str(if filled { "#" } else { "." })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Polyomino {
+    type Err = ParseError;
+
+    /// Parse rows of `#` and `.`, numbering lines from 1
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_rows(s.lines().enumerate().map(|(idx, line)| (idx + 1, line)))
+    }
+}
+
+/// Build a shape from numbered rows of `#` and `.`
+fn parse_rows<'a>(
+    rows: impl IntoIterator<Item = (usize, &'a str)>,
+) -> Result<Polyomino, ParseError> {
+    let mut cells = Vec::new();
+    let mut last_line = 0;
+    for (row, (line_no, line)) in rows.into_iter().enumerate() {
+        last_line = line_no;
+        for (col, ch) in line.chars().enumerate() {
+            match ch {
+                '#' => cells.push(Point2::new(col as i32, row as i32)),
+                '.' => {}
+                _ => {
+                    return Err(ParseError::new(
+                        line_no,
+                        col + 1,
+                        format!("expected '#' or '.' in shape, found '{}'", ch),
+                    ))
+                }
+            }
+        }
+    }
+    if cells.is_empty() {
+        return Err(ParseError::new(last_line.max(1), 1, "shape has no cells"));
+    }
+    Ok(Polyomino::new(cells))
+}
+
+/// The index in a shape header like `3:`, if `line` is one
+fn header_index(line: &str) -> Option<&str> {
+    let (index, rest) = line.split_once(':')?;
+    let index = index.trim();
+    let is_number = !index.is_empty() && index.chars().all(|ch| ch.is_ascii_digit());
+    (is_number && rest.trim().is_empty()).then_some(index)
+}
+
+/// Parse a library of shapes, each an `N:` header followed by its rows
+///
+/// Shapes must be numbered `0, 1, 2, ...` in order. Blank lines may separate
+/// them, and a row that contains a `:` ends the shape it follows. Parsing
+/// stops at the first line that is neither a header nor a row, returning
+/// the shapes with that line's 0-based index (or the line count).
+pub fn parse_library(input: &str) -> Result<(Vec<Polyomino>, usize), ParseError> {
+    let lines: Vec<&str> = input.lines().collect();
+    let mut shapes = Vec::new();
+    let mut idx = 0;
+    while idx < lines.len() {
+        let line = lines[idx];
+        if line.trim().is_empty() {
+            idx += 1;
+            continue;
+        }
+        let Some(index) = header_index(line) else {
+            break;
+        };
+        if index.parse::<usize>().ok() != Some(shapes.len()) {
+            return Err(ParseError::at(
+                idx + 1,
+                line,
+                index,
+                format!("expected shape {}, found shape {}", shapes.len(), index),
+            ));
+        }
+
+        let start = idx + 1;
+        let mut end = start;
+        while end < lines.len() && !lines[end].trim().is_empty() && !lines[end].contains(':') {
+            end += 1;
+        }
+        let rows = (start..end).map(|row| (row + 1, lines[row]));
+        let shape = parse_rows(rows).map_err(|err| {
+            if end == start {
+                ParseError::at(idx + 1, line, index, format!("shape {} has no rows", index))
+            } else {
+                err
+            }
+        })?;
+        shapes.push(shape);
+        idx = end;
+    }
+    Ok((shapes, idx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(s: &str) -> Polyomino {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_normalises() {
+        let a = Polyomino::new([Point2::new(5, 7), Point2::new(6, 7), Point2::new(5, 7)]);
+        assert_eq!(a.cells(), [Point2::new(0, 0), Point2::new(1, 0)]);
+        assert_eq!((a.area(), a.width(), a.height()), (2, 2, 1));
+        assert_eq!(shape("..#\n.##").to_string(), ".#\n##");
+    }
+
+    #[test]
+    fn test_transforms() {
+        let l = shape("#.\n#.\n##");
+        let turned = l.transform(Transform::new(false, 1));
+        assert_eq!(turned.to_string(), "###\n#..");
+        let mirrored = l.transform(Transform::new(true, 0));
+        assert_eq!(mirrored.to_string(), ".#\n.#\n##");
+        assert_eq!(l.transform(Transform::IDENTITY), l);
+        assert_eq!(l.transform_to(&mirrored), Some(Transform::new(true, 0)));
+    }
+
+    #[test]
+    fn test_symmetries() {
+        // (shape, number of distinct orientations)
+        let cases = [
+            ("##\n##", 1),
+            ("####", 2),
+            ("###\n.#.", 4),
+            ("##.\n.##", 4),
+            ("#..\n###", 8),
+            ("###\n#..\n###", 4),
+        ];
+        for (drawing, distinct) in cases {
+            let s = shape(drawing);
+            assert_eq!(s.orientations().len(), distinct, "for\n{}", drawing);
+            assert_eq!(s.symmetries().len() * distinct, 8, "for\n{}", drawing);
+            assert!(s.symmetries().contains(&Transform::IDENTITY));
+        }
+    }
+
+    #[test]
+    fn test_canonical() {
+        let s = shape("##.\n.##");
+        let z = shape(".##\n##.");
+        let t = shape("###\n.#.");
+        assert!(s.is_congruent(&z), "S and Z are mirror images");
+        assert!(!s.is_congruent(&t));
+        for (_, orientation) in t.orientations() {
+            assert_eq!(orientation.canonical(), t.canonical());
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "#x".parse::<Polyomino>(),
+            Err(ParseError::new(
+                1,
+                2,
+                "expected '#' or '.' in shape, found 'x'"
+            ))
+        );
+        assert_eq!(
+            "..\n..".parse::<Polyomino>(),
+            Err(ParseError::new(2, 1, "shape has no cells"))
+        );
+    }
+
+    #[test]
+    fn test_parse_library() {
+        let input = "0:\n##\n#.\n\n1:\n###\n4x4: 1 2\n";
+        let (shapes, rest) = parse_library(input).unwrap();
+        assert_eq!(shapes, [shape("##\n#."), shape("###")]);
+        assert_eq!(rest, 6, "stops at the region line");
+
+        assert_eq!(parse_library("").unwrap(), (vec![], 0));
+        assert_eq!(
+            parse_library("0:\n#\n\n2:\n#\n"),
+            Err(ParseError::new(4, 1, "expected shape 1, found shape 2"))
+        );
+        assert_eq!(
+            parse_library("0:\n\n1:\n#"),
+            Err(ParseError::new(1, 1, "shape 0 has no rows"))
+        );
+        assert_eq!(
+            parse_library("0:\n#\n#y\n"),
+            Err(ParseError::new(
+                3,
+                2,
+                "expected '#' or '.' in shape, found 'y'"
+            ))
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Regression checking against recorded answers
+//!
+//! Accepted answers live next to each day's input in
+//! `puzzles/dayNN/answers.toml`:
+//!
+//! ```toml
+//! # Day 1
+//! part1 = 1234
+//! part2 = "ABC"
+//! ```
+//!
+//! Only this flat subset of TOML is understood: `part1`/`part2` keys with
+//! integer or basic-string values, plus comments and blank lines. A part with
+//! no recorded answer is skipped rather than failed.
+
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::runner::DayReport;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// File name of the answers store inside a day's puzzle directory
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Recorded answers for one day, in their printed form
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Expected {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// Parse the contents of an `answers.toml` file
+pub fn parse_answers(input: &str) -> Result<Expected, ParseError> {
+    let mut expected = Expected::default();
+    for (idx, line) in input.lines().enumerate() {
+        let line_no = idx + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = trimmed
+            .split_once('=')
+            .ok_or_else(|| ParseError::at(line_no, line, trimmed, "expected 'key = value'"))?;
+        let key = key.trim();
+        let slot = match key {
+            "part1" => &mut expected.part1,
+            "part2" => &mut expected.part2,
+            _ => {
+                return Err(ParseError::at(
+                    line_no,
+                    line,
+                    key,
+                    format!("unknown key '{}', expected part1 or part2", key),
+                ))
+            }
+        };
+        if slot.is_some() {
+            return Err(ParseError::at(
+                line_no,
+                line,
+                key,
+                format!("duplicate key '{}'", key),
+            ));
+        }
+        *slot = Some(parse_value(line_no, line, value.trim())?);
+    }
+    Ok(expected)
+}
+
+/// Parse a TOML integer or basic string, allowing a trailing comment
+fn parse_value(line_no: usize, line: &str, value: &str) -> Result<String, ParseError> {
+    if let Some(rest) = value.strip_prefix('"') {
+        let end = rest
+            .find('"')
+            .ok_or_else(|| ParseError::at(line_no, line, value, "unterminated string"))?;
+        let trailing = rest[end + 1..].trim_start();
+        if !trailing.is_empty() && !trailing.starts_with('#') {
+            return Err(ParseError::at(
+                line_no,
+                line,
+                trailing,
+                "unexpected text after value",
+            ));
+        }
+        let text = &rest[..end];
+        if text.contains('\\') {
+            return Err(ParseError::at(
+                line_no,
+                line,
+                text,
+                "escape sequences are not supported",
+            ));
+        }
+        return Ok(text.to_string());
+    }
+
+    let number = value.split('#').next().unwrap_or_default().trim();
+    let digits = number.replace('_', "");
+    digits.parse::<i128>().map(|n| n.to_string()).map_err(|_| {
+        ParseError::at(
+            line_no,
+            line,
+            number,
+            format!("invalid answer '{}'", number),
+        )
+    })
+}
+
+/// Load a day's answers, returning `None` if nothing has been recorded
+pub fn load_answers(path: &Path) -> Result<Option<Expected>, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(format!("{}: {}", path.display(), err)),
+    };
+    parse_answers(&text)
+        .map(Some)
+        .map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+/// The result of comparing one part against its recorded answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    /// The part produced the recorded answer
+    Pass,
+    /// The part produced something else
+    Mismatch { expected: String, actual: Answer },
+    /// No answer has been recorded for this part
+    Unrecorded,
+    /// The part produced no answer
+    Failed(String),
+}
+
+impl Check {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Check::Mismatch { .. } | Check::Failed(_))
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Check::Pass => write!(f, "ok"),
+            Check::Mismatch { expected, actual } => {
+                write!(f, "MISMATCH: expected {}, got {}", expected, actual)
+            }
+            Check::Unrecorded => write!(f, "no recorded answer"),
+            Check::Failed(err) => write!(f, "FAILED: {}", err),
+        }
+    }
+}
+
+/// Compare a day's answers with what has been recorded
+///
+/// Returns the run error as text if the day produced no answers.
+pub fn check(report: &DayReport, expected: &Expected) -> Result<[Check; 2], String> {
+    let parts = report.parts.as_ref().map_err(ToString::to_string)?;
+    let recorded = [&expected.part1, &expected.part2];
+    Ok([0, 1].map(|idx| match (recorded[idx], &parts[idx].answer) {
+        (None, _) => Check::Unrecorded,
+        (Some(_), Err(err)) => Check::Failed(err.to_string()),
+        (Some(want), Ok(actual)) if *want == actual.to_string() => Check::Pass,
+        (Some(want), Ok(actual)) => Check::Mismatch {
+            expected: want.clone(),
+            actual: actual.clone(),
+        },
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{PartReport, RunError};
+    use std::time::Duration;
+
+    fn report(answers: [Answer; 2]) -> DayReport {
+        DayReport {
+            day: 1,
+            title: "Test Day",
+            input_hash: None,
+            parse_time: Duration::ZERO,
+            parts: Ok(answers.map(|answer| PartReport {
+                answer: Ok(answer),
+                elapsed: Duration::ZERO,
+            })),
+        }
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        let input = "# Day 1\npart1 = 1_234 # accepted\n\npart2 = \"ABC\"\n";
+        assert_eq!(
+            parse_answers(input),
+            Ok(Expected {
+                part1: Some("1234".to_string()),
+                part2: Some("ABC".to_string()),
+            })
+        );
+        assert_eq!(parse_answers("part2 = -7").unwrap().part1, None);
+    }
+
+    #[test]
+    fn test_parse_answers_errors() {
+        assert_eq!(
+            parse_answers("part1 = 1\npart3 = 2"),
+            Err(ParseError::new(
+                2,
+                1,
+                "unknown key 'part3', expected part1 or part2"
+            ))
+        );
+        assert_eq!(
+            parse_answers("part1 = 1\npart1 = 2").unwrap_err().message,
+            "duplicate key 'part1'"
+        );
+        assert_eq!(
+            parse_answers("part1 = 12x"),
+            Err(ParseError::new(1, 9, "invalid answer '12x'"))
+        );
+        assert!(parse_answers("part1 = \"open").is_err());
+        assert!(parse_answers("part1").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let expected = Expected {
+            part1: Some("3".to_string()),
+            part2: Some("6".to_string()),
+        };
+        let checks = check(&report([Answer::Signed(3), Answer::Unsigned(7)]), &expected).unwrap();
+        assert_eq!(checks[0], Check::Pass);
+        assert_eq!(
+            checks[1],
+            Check::Mismatch {
+                expected: "6".to_string(),
+                actual: Answer::Unsigned(7),
+            }
+        );
+
+        let unrecorded = check(
+            &report([Answer::Signed(3), Answer::NotApplicable]),
+            &Expected::default(),
+        );
+        assert_eq!(unrecorded.unwrap(), [Check::Unrecorded, Check::Unrecorded]);
+        assert_eq!(checks[1].to_string(), "MISMATCH: expected 6, got 7");
+
+        let mut timed_out = report([Answer::Signed(3), Answer::Signed(6)]);
+        if let Ok(parts) = &mut timed_out.parts {
+            parts[1].answer = Err(RunError::TimedOut(Duration::from_secs(1)));
+        }
+        let checks = check(&timed_out, &expected).unwrap();
+        assert_eq!(checks[1], Check::Failed("timed out after 1.00s".to_string()));
+        assert!(checks[1].is_failure());
+    }
+
+    #[test]
+    fn test_load_missing_answers() {
+        assert_eq!(
+            load_answers(Path::new("/nonexistent/answers.toml")),
+            Ok(None)
+        );
+    }
+}